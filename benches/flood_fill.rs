@@ -0,0 +1,49 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use minesweeper::{board::Board, game_types::CellValue, game_utils::open_cell};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vctr2::vector2::Vector2;
+
+/// A closed board with mines spread at the given density, keeping the center free.
+fn board(size: u16, density: f64) -> Board {
+    let mut rng = StdRng::seed_from_u64(0);
+    let center = size / 2;
+    let mut board = Board::new(Vector2::new(size, size));
+
+    for position in board.positions().collect::<Vec<_>>() {
+        let near_center = position.x.abs_diff(center) <= 1 && position.y.abs_diff(center) <= 1;
+
+        if !near_center && rng.gen_bool(density) {
+            board[position].value = CellValue::Bomb;
+        }
+    }
+    board.update_bombs_around();
+
+    board
+}
+
+fn flood_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("open_cell");
+    group.sample_size(10);
+
+    for (name, size, density) in [
+        ("expert", 30, 0.2),
+        ("500x500 low density", 500, 0.01),
+        ("1000x1000 empty", 1000, 0.0),
+    ] {
+        let board = board(size, density);
+        let center = Vector2::new(size / 2, size / 2);
+
+        group.bench_function(name, |b| {
+            b.iter_batched_ref(
+                || board.clone(),
+                |board| open_cell(board, &center),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, flood_fill);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game_types::{CellState, CellValue},
+};
+
+// --- Screen reader texts --- //
+
+// Rows and columns are counted from 1, as they are spoken to the player.
+
+/// Describes a single cell, e.g. "row 3 column 5, 2 mines nearby, 1 flagged neighbour".
+pub(crate) fn describe_cell(board: &Board, position: &Vector2<u16>) -> String {
+    let flagged = board
+        .neighbours(position)
+        .filter(|n| board[*n].state == CellState::Flagged)
+        .count();
+
+    let mut text = format!(
+        "row {} column {}, {}",
+        position.y + 1,
+        position.x + 1,
+        cell_state_text(board, position)
+    );
+
+    if flagged > 0 {
+        text.push_str(&format!(", {}", plural(flagged, "flagged neighbour")));
+    }
+
+    text
+}
+
+/// Reads a whole row, merging neighbouring cells in the same state, e.g.
+/// "row 2: columns 1 to 4 no mines nearby, column 5 1 mine nearby, columns 6 to 9 closed".
+pub(crate) fn describe_row(board: &Board, y: u16) -> String {
+    let mut parts: Vec<(u16, u16, String)> = Vec::new();
+
+    for x in 0..board.size().x {
+        let text = cell_state_text(board, &Vector2::new(x, y));
+
+        match parts.last_mut() {
+            Some((_, last, last_text)) if *last_text == text => *last = x,
+            _ => parts.push((x, x, text)),
+        }
+    }
+
+    let parts: Vec<String> = parts
+        .into_iter()
+        .map(|(first, last, text)| {
+            if first == last {
+                format!("column {} {}", first + 1, text)
+            } else {
+                format!("columns {} to {} {}", first + 1, last + 1, text)
+            }
+        })
+        .collect();
+
+    format!("row {}: {}", y + 1, parts.join(", "))
+}
+
+fn cell_state_text(board: &Board, position: &Vector2<u16>) -> String {
+    let cell = board[*position];
+
+    match cell.state {
+        CellState::Closed => "closed".to_string(),
+        CellState::Flagged => "flagged".to_string(),
+        CellState::Questioned => "question mark".to_string(),
+        CellState::Opened => match cell.value {
+            CellValue::Bomb => "mine".to_string(),
+            CellValue::Empty => match cell.bombs_around {
+                0 => "no mines nearby".to_string(),
+                count => format!("{} nearby", plural(count as usize, "mine")),
+            },
+        },
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        let mut board = Board::new(Vector2::new(4, 2));
+        board[Vector2::new(0, 0)].value = CellValue::Bomb;
+        board[Vector2::new(0, 0)].state = CellState::Flagged;
+        board[Vector2::new(1, 0)].value = CellValue::Bomb;
+        board[Vector2::new(1, 1)].state = CellState::Opened;
+        board[Vector2::new(2, 1)].state = CellState::Opened;
+        board[Vector2::new(3, 1)].state = CellState::Opened;
+        board.update_bombs_around();
+
+        board
+    }
+
+    #[test]
+    fn cell() {
+        let board = board();
+
+        assert_eq!(
+            describe_cell(&board, &Vector2::new(1, 1)),
+            "row 2 column 2, 2 mines nearby, 1 flagged neighbour"
+        );
+        assert_eq!(
+            describe_cell(&board, &Vector2::new(3, 1)),
+            "row 2 column 4, no mines nearby"
+        );
+    }
+
+    #[test]
+    fn row() {
+        let board = board();
+
+        assert_eq!(
+            describe_row(&board, 0),
+            "row 1: column 1 flagged, columns 2 to 4 closed"
+        );
+        assert_eq!(
+            describe_row(&board, 1),
+            "row 2: column 1 closed, column 2 2 mines nearby, column 3 1 mine nearby, column 4 no mines nearby"
+        );
+    }
+}
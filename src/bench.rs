@@ -0,0 +1,264 @@
+use std::{
+    io::{BufRead, BufReader, Result, Write},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vctr2::vector2::Vector2;
+
+use crate::{
+    cli::{BenchArgs, Difficulty},
+    game::{Game, GameStatus},
+    game_types::{CellState, GameSettings},
+    solver::{self, Action},
+    stdio::{self, Session},
+};
+
+/// How one game went.
+#[derive(Debug, Clone, Copy)]
+struct GameResult {
+    won: bool,
+    /// Cells revealed that the solver could not prove safe, not counting the first one.
+    guesses: u32,
+    time: Duration,
+}
+
+/// Plays `args.games` boards per difficulty on every core and prints a table of the results.
+pub(crate) fn run(args: &BenchArgs) -> Result<()> {
+    let difficulties = if args.difficulty.is_empty() {
+        Difficulty::value_variants().to_vec()
+    } else {
+        args.difficulty.clone()
+    };
+
+    println!(
+        "{:<14}{:>7}{:>20}{:>20}{:>24}",
+        "difficulty", "games", "win rate", "guesses/game", "time/game"
+    );
+
+    for difficulty in difficulties {
+        let results = play_all(args, difficulty.game_settings())?;
+
+        let (win_rate, win_rate_margin) =
+            mean_and_margin(results.iter().map(|r| if r.won { 100.0 } else { 0.0 }));
+        let (guesses, guesses_margin) = mean_and_margin(results.iter().map(|r| r.guesses as f64));
+        let (time, time_margin) =
+            mean_and_margin(results.iter().map(|r| r.time.as_secs_f64() * 1000.0));
+
+        println!(
+            "{:<14}{:>7}{:>20}{:>20}{:>24}",
+            difficulty.to_possible_value().unwrap().get_name(),
+            results.len(),
+            format!("{:.1}% ± {:.1}%", win_rate, win_rate_margin),
+            format!("{:.2} ± {:.2}", guesses, guesses_margin),
+            format!("{:.3} ms ± {:.3} ms", time, time_margin),
+        );
+    }
+
+    println!("\n± is the 95% confidence interval");
+    Ok(())
+}
+
+fn play_all(args: &BenchArgs, game_settings: GameSettings) -> Result<Vec<GameResult>> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    let games = args.games as u64;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                scope.spawn(move || {
+                    (thread..games)
+                        .step_by(threads as usize)
+                        .map(|game| {
+                            let seed = args.seed.wrapping_add(game);
+
+                            match &args.bot {
+                                Some(bot) => play_bot(bot, game_settings, seed, args.no_guess),
+                                None => Ok(play_solver(game_settings, seed, args.no_guess)),
+                            }
+                        })
+                        .collect::<Result<Vec<GameResult>>>()
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(games as usize);
+        for handle in handles {
+            results.extend(handle.join().expect("bench threads don't panic")?);
+        }
+
+        Ok(results)
+    })
+}
+
+/// Lets the built-in solver play, it guesses a random unknown cell when it is stuck.
+fn play_solver(game_settings: GameSettings, seed: u64, no_guess: bool) -> GameResult {
+    let started_at = Instant::now();
+    let mut game = Game::new(game_settings, seed).with_no_guess(no_guess);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut guesses = 0;
+
+    game.reveal(&Vector2::new(
+        game_settings.size.x / 2,
+        game_settings.size.y / 2,
+    ));
+
+    while game.status() == GameStatus::Playing {
+        let board = game.visible_board();
+        let deductions = solver::find_deductions(&board);
+
+        if deductions.is_empty() {
+            let unknown: Vec<Vector2<u16>> = board
+                .positions()
+                .filter(|p| matches!(board[*p].state, CellState::Closed | CellState::Questioned))
+                .collect();
+            if unknown.is_empty() {
+                break;
+            }
+
+            guesses += 1;
+            game.reveal(&unknown[rng.gen_range(0..unknown.len())]);
+            continue;
+        }
+
+        for deduction in deductions {
+            match deduction.action {
+                Action::Open => {
+                    game.reveal(&deduction.position);
+                }
+                Action::Flag => {
+                    game.flag(&deduction.position);
+                }
+            }
+        }
+    }
+
+    GameResult {
+        won: game.status() == GameStatus::Won,
+        guesses,
+        time: started_at.elapsed(),
+    }
+}
+
+/// Runs the bot for one game, answering its commands like `--stdio` does until the game is over.
+///
+/// The game already exists when the bot starts, `{"cmd": "new"}` without fields shows it the
+/// board. A bot that quits early loses the game.
+fn play_bot(
+    bot: &str,
+    game_settings: GameSettings,
+    seed: u64,
+    no_guess: bool,
+) -> Result<GameResult> {
+    let started_at = Instant::now();
+    let mut session = Session::new(game_settings, Some(seed), no_guess);
+    let mut guesses = 0;
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(bot)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut input = child.stdin.take().expect("stdin is piped");
+    let output = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+    for line in output.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let command = serde_json::from_str::<stdio::Command>(&line).ok();
+        if let Some(stdio::Command::Reveal { x, y }) = command {
+            if is_guess(session.game(), &Vector2::new(x, y)) {
+                guesses += 1;
+            }
+        }
+
+        let answer = session.handle(&line);
+        let game = session.game();
+        if game.settings() != game_settings || game.seed() != seed {
+            return Err(std::io::Error::other(format!(
+                "the bot changed the game with {}",
+                line
+            )));
+        }
+
+        // a bot that stops reading right after the last move is fine
+        let _ = writeln!(input, "{}", answer).and_then(|()| input.flush());
+        if game.status() != GameStatus::Playing {
+            break;
+        }
+    }
+
+    drop(input);
+    let _ = child.kill();
+    child.wait()?;
+
+    Ok(GameResult {
+        won: session.game().status() == GameStatus::Won,
+        guesses,
+        time: started_at.elapsed(),
+    })
+}
+
+/// Whether revealing the cell is a guess: not the first reveal and not proven safe.
+fn is_guess(game: &Game, position: &Vector2<u16>) -> bool {
+    if !game.mines_placed() {
+        return false;
+    }
+
+    let board = game.visible_board();
+    if !matches!(
+        board.get(position).map(|cell| cell.state),
+        Some(CellState::Closed | CellState::Questioned)
+    ) {
+        return false;
+    }
+
+    !solver::find_deductions(&board)
+        .iter()
+        .any(|d| d.action == Action::Open && d.position == *position)
+}
+
+/// Mean of the values and half the width of its 95% confidence interval.
+fn mean_and_margin(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let values: Vec<f64> = values.collect();
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return (values.first().copied().unwrap_or(0.0), 0.0);
+    }
+
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, 1.96 * (variance / n).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DIFFICULY_EXPERT;
+
+    #[test]
+    fn solver_needs_no_guesses_on_no_guess_boards() {
+        for seed in 0..5 {
+            let result = play_solver(DIFFICULY_EXPERT, seed, true);
+
+            assert!(result.won);
+            assert_eq!(result.guesses, 0);
+        }
+    }
+
+    #[test]
+    fn margin() {
+        let (mean, margin) = mean_and_margin([1.0, 2.0, 3.0].into_iter());
+
+        assert_eq!(mean, 2.0);
+        assert!((margin - 1.96 / 3f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,214 @@
+use std::ops::{Index, IndexMut};
+
+use vctr2::vector2::Vector2;
+
+use crate::{
+    game_types::{Cell, CellState, CellValue},
+    game_utils::neighbours,
+};
+
+/// The cells of a game, stored row by row in one contiguous buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    size: Vector2<u16>,
+    cells: Vec<Cell>,
+}
+
+impl Board {
+    /// A board of closed cells without bombs.
+    pub fn new(size: Vector2<u16>) -> Self {
+        let closed = Cell {
+            state: CellState::Closed,
+            value: CellValue::Empty,
+            bombs_around: 0,
+        };
+
+        Self {
+            size,
+            cells: vec![closed; size.x as usize * size.y as usize],
+        }
+    }
+
+    pub fn size(&self) -> Vector2<u16> {
+        self.size
+    }
+
+    pub fn contains(&self, position: &Vector2<u16>) -> bool {
+        position.x < self.size.x && position.y < self.size.y
+    }
+
+    pub fn get(&self, position: &Vector2<u16>) -> Option<&Cell> {
+        self.contains(position)
+            .then(|| &self.cells[self.index_of(position)])
+    }
+
+    pub fn get_mut(&mut self, position: &Vector2<u16>) -> Option<&mut Cell> {
+        if self.contains(position) {
+            let index = self.index_of(position);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn neighbours(&self, position: &Vector2<u16>) -> impl Iterator<Item = Vector2<u16>> {
+        neighbours(&self.size, position)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks_exact(self.size.x as usize)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &Cell> {
+        self.cells.iter()
+    }
+
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut Cell> {
+        self.cells.iter_mut()
+    }
+
+    /// Every position of the board, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vector2<u16>> {
+        let size = self.size;
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Vector2::new(x, y)))
+    }
+
+    pub fn bombs_around(&self, position: &Vector2<u16>) -> u8 {
+        self.neighbours(position)
+            .filter(|n| self[*n].value == CellValue::Bomb)
+            .count() as u8
+    }
+
+    /// Stores the number of bombs around every cell in it, to be called after placing the bombs.
+    pub fn update_bombs_around(&mut self) {
+        for position in self.positions() {
+            let bombs_around = self.bombs_around(&position);
+            self[position].bombs_around = bombs_around;
+        }
+    }
+
+    /// Packs the board into 4 bytes of size and one byte per cell.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + self.cells.len());
+        bytes.extend_from_slice(&self.size.x.to_le_bytes());
+        bytes.extend_from_slice(&self.size.y.to_le_bytes());
+
+        bytes.extend(self.cells.iter().map(|cell| {
+            let state = match cell.state {
+                CellState::Closed => 0,
+                CellState::Opened => 1,
+                CellState::Flagged => 2,
+                CellState::Questioned => 3,
+            };
+            let bomb = match cell.value {
+                CellValue::Empty => 0,
+                CellValue::Bomb => 1,
+            };
+
+            state | bomb << 2
+        }));
+
+        bytes
+    }
+
+    /// Reads a board written by [`Board::to_bytes`], `None` when the bytes are not a board.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (size, cells) = bytes.split_at_checked(4)?;
+        let size = Vector2::new(
+            u16::from_le_bytes([size[0], size[1]]),
+            u16::from_le_bytes([size[2], size[3]]),
+        );
+
+        if cells.len() != size.x as usize * size.y as usize {
+            return None;
+        }
+
+        let cells = cells
+            .iter()
+            .map(|byte| {
+                let state = match byte & 0b11 {
+                    0 => CellState::Closed,
+                    1 => CellState::Opened,
+                    2 => CellState::Flagged,
+                    _ => CellState::Questioned,
+                };
+                let value = match byte >> 2 {
+                    0 => CellValue::Empty,
+                    1 => CellValue::Bomb,
+                    _ => return None,
+                };
+
+                Some(Cell {
+                    state,
+                    value,
+                    bombs_around: 0,
+                })
+            })
+            .collect::<Option<Vec<Cell>>>()?;
+
+        let mut board = Self { size, cells };
+        board.update_bombs_around();
+        Some(board)
+    }
+
+    fn index_of(&self, position: &Vector2<u16>) -> usize {
+        position.y as usize * self.size.x as usize + position.x as usize
+    }
+}
+
+impl Index<Vector2<u16>> for Board {
+    type Output = Cell;
+
+    fn index(&self, position: Vector2<u16>) -> &Cell {
+        self.get(&position).unwrap_or_else(|| {
+            panic!(
+                "position {}x{} outside of the {}x{} board",
+                position.x, position.y, self.size.x, self.size.y
+            )
+        })
+    }
+}
+
+impl IndexMut<Vector2<u16>> for Board {
+    fn index_mut(&mut self, position: Vector2<u16>) -> &mut Cell {
+        let size = self.size;
+
+        self.get_mut(&position).unwrap_or_else(|| {
+            panic!(
+                "position {}x{} outside of the {}x{} board",
+                position.x, position.y, size.x, size.y
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let board = Board::new(Vector2::new(3, 2));
+
+        assert!(board.get(&Vector2::new(2, 1)).is_some());
+        assert!(board.get(&Vector2::new(3, 0)).is_none());
+        assert!(board.get(&Vector2::new(0, 2)).is_none());
+        assert_eq!(board.rows().count(), 2);
+        assert_eq!(board.positions().last(), Some(Vector2::new(2, 1)));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut board = Board::new(Vector2::new(3, 2));
+        board[Vector2::new(0, 0)].value = CellValue::Bomb;
+        board[Vector2::new(0, 0)].state = CellState::Flagged;
+        board[Vector2::new(2, 1)].state = CellState::Opened;
+        board[Vector2::new(1, 1)].state = CellState::Questioned;
+        board.update_bombs_around();
+
+        let bytes = board.to_bytes();
+        assert_eq!(bytes.len(), 4 + 6);
+        assert_eq!(Board::from_bytes(&bytes), Some(board));
+        assert_eq!(Board::from_bytes(&bytes[..8]), None);
+    }
+}
@@ -0,0 +1,510 @@
+use std::fmt::Write;
+
+use ratatui::style::Color;
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game_types::{Cell, CellState, CellValue},
+    theme::THEME_CLASSIC,
+};
+
+// --- Pictures of finished boards --- //
+
+// Every cell is drawn from a few shapes, written out as SVG elements or filled pixel by pixel
+// for PNG. Numbers in PNGs come from a 5x7 bitmap font, so no font has to be installed.
+
+/// Side of a cell in pixels.
+const CELL: f32 = 24.0;
+
+const BORDER: Rgb = Rgb(128, 128, 128);
+const CLOSED: Rgb = Rgb(192, 192, 192);
+const OPENED: Rgb = Rgb(230, 230, 230);
+const EXPLODED: Rgb = Rgb(255, 0, 0);
+const FLAG: Rgb = Rgb(255, 0, 0);
+const BLACK: Rgb = Rgb(0, 0, 0);
+const WHITE: Rgb = Rgb(255, 255, 255);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rgb(u8, u8, u8);
+
+impl Rgb {
+    fn from_color(color: Color) -> Self {
+        match color {
+            Color::Rgb(r, g, b) => Self(r, g, b),
+            _ => BLACK,
+        }
+    }
+}
+
+enum Shape {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        fill: Rgb,
+    },
+    Circle {
+        x: f32,
+        y: f32,
+        radius: f32,
+        fill: Rgb,
+    },
+    Polygon {
+        points: Vec<(f32, f32)>,
+        fill: Rgb,
+    },
+    Line {
+        from: (f32, f32),
+        to: (f32, f32),
+        width: f32,
+        stroke: Rgb,
+    },
+    /// A single character centered on the point.
+    Text {
+        x: f32,
+        y: f32,
+        symbol: char,
+        fill: Rgb,
+    },
+}
+
+/// The board as an SVG image, with the mine that lost the game on red.
+pub(crate) fn svg(board: &Board, exploded: Option<Vector2<u16>>) -> String {
+    let (width, height) = image_size(board);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\">\n",
+        width, height
+    );
+
+    for shape in shapes(board, exploded) {
+        // writing to a String can't fail
+        let _ = match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                fill,
+            } => writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x,
+                y,
+                width,
+                height,
+                hex(fill)
+            ),
+            Shape::Circle { x, y, radius, fill } => writeln!(
+                svg,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                x,
+                y,
+                radius,
+                hex(fill)
+            ),
+            Shape::Polygon { points, fill } => writeln!(
+                svg,
+                "<polygon points=\"{}\" fill=\"{}\"/>",
+                points
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                hex(fill)
+            ),
+            Shape::Line {
+                from,
+                to,
+                width,
+                stroke,
+            } => writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                from.0,
+                from.1,
+                to.0,
+                to.1,
+                hex(stroke),
+                width
+            ),
+            Shape::Text { x, y, symbol, fill } => writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-family=\"monospace\" font-size=\"18\" \
+                 font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                x,
+                y,
+                hex(fill),
+                symbol
+            ),
+        };
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The board as a PNG image, drawn like [`svg`].
+#[cfg(feature = "png")]
+pub(crate) fn png(board: &Board, exploded: Option<Vector2<u16>>) -> Vec<u8> {
+    let (width, height) = image_size(board);
+    let mut pixels = vec![0; width as usize * height as usize * 3];
+
+    for shape in shapes(board, exploded) {
+        raster::fill(&mut pixels, width, height, &shape);
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .expect("writing a PNG to memory doesn't fail");
+
+    bytes
+}
+
+fn image_size(board: &Board) -> (u32, u32) {
+    let size = board.size();
+    ((size.x as f32 * CELL) as u32, (size.y as f32 * CELL) as u32)
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
+
+fn shapes(board: &Board, exploded: Option<Vector2<u16>>) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+
+    for position in board.positions() {
+        let x = position.x as f32 * CELL;
+        let y = position.y as f32 * CELL;
+        let cell = board[position];
+
+        let background = match cell.state {
+            CellState::Opened if Some(position) == exploded => EXPLODED,
+            CellState::Opened => OPENED,
+            _ => CLOSED,
+        };
+        shapes.push(Shape::Rect {
+            x,
+            y,
+            width: CELL,
+            height: CELL,
+            fill: BORDER,
+        });
+        shapes.push(Shape::Rect {
+            x: x + 1.0,
+            y: y + 1.0,
+            width: CELL - 1.0,
+            height: CELL - 1.0,
+            fill: background,
+        });
+
+        cell_shapes(&mut shapes, x, y, &cell);
+    }
+
+    shapes
+}
+
+/// What is drawn on top of the background of a cell at `x`, `y`.
+fn cell_shapes(shapes: &mut Vec<Shape>, x: f32, y: f32, cell: &Cell) {
+    let center = (x + CELL / 2.0, y + CELL / 2.0);
+
+    match (cell.state, cell.value) {
+        (CellState::Opened, CellValue::Empty) if cell.bombs_around > 0 => {
+            shapes.push(Shape::Text {
+                x: center.0,
+                y: center.1,
+                symbol: char::from(b'0' + cell.bombs_around),
+                fill: Rgb::from_color(THEME_CLASSIC.number(cell.bombs_around)),
+            });
+        }
+        (CellState::Opened, CellValue::Empty) | (CellState::Closed, _) => {}
+        (CellState::Opened, CellValue::Bomb) => {
+            for (from, to) in [((-8.0, 0.0), (8.0, 0.0)), ((0.0, -8.0), (0.0, 8.0))] {
+                shapes.push(Shape::Line {
+                    from: (center.0 + from.0, center.1 + from.1),
+                    to: (center.0 + to.0, center.1 + to.1),
+                    width: 2.0,
+                    stroke: BLACK,
+                });
+            }
+            shapes.push(Shape::Circle {
+                x: center.0,
+                y: center.1,
+                radius: 6.0,
+                fill: BLACK,
+            });
+            shapes.push(Shape::Circle {
+                x: center.0 - 2.0,
+                y: center.1 - 2.0,
+                radius: 1.5,
+                fill: WHITE,
+            });
+        }
+        (CellState::Flagged, value) => {
+            shapes.push(Shape::Polygon {
+                points: vec![(x + 9.0, y + 5.0), (x + 18.0, y + 9.0), (x + 9.0, y + 13.0)],
+                fill: FLAG,
+            });
+            shapes.push(Shape::Rect {
+                x: x + 8.0,
+                y: y + 5.0,
+                width: 2.0,
+                height: 13.0,
+                fill: BLACK,
+            });
+            shapes.push(Shape::Rect {
+                x: x + 6.0,
+                y: y + 17.0,
+                width: 10.0,
+                height: 2.0,
+                fill: BLACK,
+            });
+
+            // crossed out when there was no mine under it
+            if value == CellValue::Empty {
+                for (from, to) in [((4.0, 4.0), (20.0, 20.0)), ((20.0, 4.0), (4.0, 20.0))] {
+                    shapes.push(Shape::Line {
+                        from: (x + from.0, y + from.1),
+                        to: (x + to.0, y + to.1),
+                        width: 2.0,
+                        stroke: BLACK,
+                    });
+                }
+            }
+        }
+        (CellState::Questioned, _) => {
+            shapes.push(Shape::Text {
+                x: center.0,
+                y: center.1,
+                symbol: '?',
+                fill: BLACK,
+            });
+        }
+    }
+}
+
+/// Fills shapes into an RGB buffer, a pixel belongs to a shape when its center is inside.
+#[cfg(feature = "png")]
+mod raster {
+    use super::{Rgb, Shape};
+
+    /// 5x7 glyphs of the symbols drawn in cells, the top row first.
+    const GLYPHS: [(char, [u8; 7]); 9] = [
+        (
+            '1',
+            [
+                0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+            ],
+        ),
+        (
+            '2',
+            [
+                0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+            ],
+        ),
+        (
+            '3',
+            [
+                0b11110, 0b00001, 0b00001, 0b01110, 0b00001, 0b00001, 0b11110,
+            ],
+        ),
+        (
+            '4',
+            [
+                0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+            ],
+        ),
+        (
+            '5',
+            [
+                0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+            ],
+        ),
+        (
+            '6',
+            [
+                0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+            ],
+        ),
+        (
+            '7',
+            [
+                0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+            ],
+        ),
+        (
+            '8',
+            [
+                0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+            ],
+        ),
+        (
+            '?',
+            [
+                0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100,
+            ],
+        ),
+    ];
+
+    /// Pixels per dot of a glyph.
+    const GLYPH_SCALE: f32 = 2.0;
+
+    pub(super) fn fill(pixels: &mut [u8], width: u32, height: u32, shape: &Shape) {
+        let (left, top, right, bottom, fill) = bounds(shape);
+        let columns = left.max(0.0) as u32..(right.ceil() as u32).min(width);
+        let rows = top.max(0.0) as u32..(bottom.ceil() as u32).min(height);
+
+        for py in rows {
+            for px in columns.clone() {
+                if contains(shape, px as f32 + 0.5, py as f32 + 0.5) {
+                    let index = (py as usize * width as usize + px as usize) * 3;
+                    pixels[index..index + 3].copy_from_slice(&[fill.0, fill.1, fill.2]);
+                }
+            }
+        }
+    }
+
+    fn bounds(shape: &Shape) -> (f32, f32, f32, f32, Rgb) {
+        match *shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                fill,
+            } => (x, y, x + width, y + height, fill),
+            Shape::Circle { x, y, radius, fill } => {
+                (x - radius, y - radius, x + radius, y + radius, fill)
+            }
+            Shape::Polygon { ref points, fill } => {
+                let xs = points.iter().map(|p| p.0);
+                let ys = points.iter().map(|p| p.1);
+                (
+                    xs.clone().fold(f32::MAX, f32::min),
+                    ys.clone().fold(f32::MAX, f32::min),
+                    xs.fold(f32::MIN, f32::max),
+                    ys.fold(f32::MIN, f32::max),
+                    fill,
+                )
+            }
+            Shape::Line {
+                from,
+                to,
+                width,
+                stroke,
+            } => (
+                from.0.min(to.0) - width,
+                from.1.min(to.1) - width,
+                from.0.max(to.0) + width,
+                from.1.max(to.1) + width,
+                stroke,
+            ),
+            Shape::Text { x, y, fill, .. } => (
+                x - 2.5 * GLYPH_SCALE,
+                y - 3.5 * GLYPH_SCALE,
+                x + 2.5 * GLYPH_SCALE,
+                y + 3.5 * GLYPH_SCALE,
+                fill,
+            ),
+        }
+    }
+
+    fn contains(shape: &Shape, px: f32, py: f32) -> bool {
+        match *shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => px >= x && px < x + width && py >= y && py < y + height,
+            Shape::Circle { x, y, radius, .. } => {
+                (px - x).powi(2) + (py - y).powi(2) <= radius * radius
+            }
+            Shape::Polygon { ref points, .. } => {
+                // even-odd rule
+                let mut inside = false;
+                for (i, a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    if (a.1 > py) != (b.1 > py) && px < a.0 + (py - a.1) / (b.1 - a.1) * (b.0 - a.0)
+                    {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+            Shape::Line {
+                from, to, width, ..
+            } => {
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let t = (((px - from.0) * dx + (py - from.1) * dy) / (dx * dx + dy * dy))
+                    .clamp(0.0, 1.0);
+                let (cx, cy) = (from.0 + t * dx, from.1 + t * dy);
+                (px - cx).powi(2) + (py - cy).powi(2) <= (width / 2.0).powi(2)
+            }
+            Shape::Text { x, y, symbol, .. } => {
+                let Some((_, rows)) = GLYPHS.iter().find(|(glyph, _)| *glyph == symbol) else {
+                    return false;
+                };
+                let column = ((px - (x - 2.5 * GLYPH_SCALE)) / GLYPH_SCALE) as usize;
+                let row = ((py - (y - 3.5 * GLYPH_SCALE)) / GLYPH_SCALE) as usize;
+
+                row < 7 && column < 5 && rows[row] & (0b10000 >> column) != 0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lost_board() -> Board {
+        // a wrong flag next to the mine that was hit
+        let mut board = Board::new(Vector2::new(3, 1));
+        board[Vector2::new(0, 0)].value = CellValue::Bomb;
+        board[Vector2::new(0, 0)].state = CellState::Opened;
+        board[Vector2::new(1, 0)].state = CellState::Opened;
+        board[Vector2::new(2, 0)].state = CellState::Flagged;
+        board.update_bombs_around();
+        board
+    }
+
+    #[test]
+    fn draws_svg() {
+        let svg = svg(&lost_board(), Some(Vector2::new(0, 0)));
+
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"72\" height=\"24\"")
+        );
+        assert!(svg.contains("<rect x=\"1\" y=\"1\" width=\"23\" height=\"23\" fill=\"#ff0000\"/>"));
+        assert!(svg.contains(">1</text>"));
+        // the spikes of the mine and the two lines crossing out the flag
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert_eq!(svg.matches("<line").count(), 2 + 2);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn draws_png() {
+        let bytes = png(&lost_board(), Some(Vector2::new(0, 0)));
+        let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((reader.info().width, reader.info().height), (72, 24));
+        // the corner of the exploded cell is red, the middle of the mine is black
+        assert_eq!(pixels[(2 * 72 + 2) * 3..(2 * 72 + 2) * 3 + 3], [255, 0, 0]);
+        assert_eq!(
+            pixels[(14 * 72 + 14) * 3..(14 * 72 + 14) * 3 + 3],
+            [0, 0, 0]
+        );
+    }
+}
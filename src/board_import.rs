@@ -0,0 +1,305 @@
+use vctr2::vector2::Vector2;
+
+use crate::{board::Board, game_types::CellValue};
+
+// --- Mine layouts of other minesweeper programs --- //
+
+// MBF, the board files of Minesweeper Arbiter and Minesweeper X:
+//   u8 width, u8 height, u16 mines, then u8 x, u8 y for every mine
+//
+// AVF, the videos of Minesweeper Arbiter:
+//   u8 version, 4 bytes, u8 mode: 3 beginner (8x8, 10 mines), 4 intermediate (16x16, 40),
+//   5 expert (30x16, 99) or 6 custom followed by u8 width - 1, u8 height - 1, u16 mines,
+//   then u8 row + 1, u8 column + 1 for every mine
+//
+// RMV, the videos of Viennasweeper, file type 1:
+//   "*rmv", u16 file type, u16 lengths of the result string, version info, player info, board,
+//   preflags and properties sections, u32 length of the video, u16 length of the checksum,
+//   then the sections in that order. The board section is laid out like MBF.
+//
+// Numbers are big endian. Only the mine layout is read, the moves of videos are left out.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Mbf,
+    Avf,
+    Rmv,
+}
+
+impl Format {
+    /// The format of files with the extension, `None` for other files.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "mbf" => Some(Self::Mbf),
+            "avf" => Some(Self::Avf),
+            "rmv" => Some(Self::Rmv),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Mbf => "MBF",
+            Self::Avf => "AVF",
+            Self::Rmv => "RMV",
+        }
+    }
+}
+
+/// Reads the mine layout of a file, every cell of the board is closed.
+pub fn parse(format: Format, bytes: &[u8]) -> Result<Board, String> {
+    let mut reader = Reader {
+        format,
+        bytes,
+        position: 0,
+    };
+
+    match format {
+        Format::Mbf => {
+            let board = reader.mbf_board()?;
+            reader.expect_end()?;
+            Ok(board)
+        }
+        Format::Avf => reader.avf_board(),
+        Format::Rmv => reader.rmv_board(),
+    }
+}
+
+struct Reader<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn mbf_board(&mut self) -> Result<Board, String> {
+        let size = Vector2::new(self.u8()? as u16, self.u8()? as u16);
+        let mines = self.u16()?;
+
+        self.mines(size, mines, |reader| Ok((reader.u8()?, reader.u8()?)))
+    }
+
+    fn avf_board(&mut self) -> Result<Board, String> {
+        self.skip(5)?;
+
+        let (size, mines) = match self.u8()? {
+            3 => (Vector2::new(8, 8), 10),
+            4 => (Vector2::new(16, 16), 40),
+            5 => (Vector2::new(30, 16), 99),
+            6 => {
+                let size = Vector2::new(self.u8()? as u16 + 1, self.u8()? as u16 + 1);
+                (size, self.u16()?)
+            }
+            mode => {
+                return Err(format!(
+                    "AVF mode {} is not supported, only beginner, intermediate, expert and \
+                     custom boards are",
+                    mode
+                ))
+            }
+        };
+
+        self.mines(size, mines, |reader| {
+            let (row, column) = (reader.u8()?, reader.u8()?);
+            if row == 0 || column == 0 {
+                return Err("the AVF file has a mine at row or column 0".to_string());
+            }
+            Ok((column - 1, row - 1))
+        })
+    }
+
+    fn rmv_board(&mut self) -> Result<Board, String> {
+        if self.take(4)? != b"*rmv" {
+            return Err("not an RMV file".to_string());
+        }
+
+        let file_type = self.u16()?;
+        if file_type != 1 {
+            return Err(format!(
+                "RMV file type {} is not supported, only 1 is",
+                file_type
+            ));
+        }
+
+        let result_length = self.u16()? as usize;
+        let version_length = self.u16()? as usize;
+        let player_length = self.u16()? as usize;
+        let board_length = self.u16()? as usize;
+        // preflags, properties, video and checksum come after the board
+        self.skip(2 + 2 + 4 + 2)?;
+
+        self.skip(result_length + version_length + player_length)?;
+        let start = self.position;
+        let board = self.mbf_board()?;
+
+        if self.position - start != board_length {
+            return Err(format!(
+                "the board section of the RMV file should be {} bytes long but is {}, this \
+                 variant is not supported",
+                board_length,
+                self.position - start
+            ));
+        }
+
+        Ok(board)
+    }
+
+    /// Places the mines read with `position`, which gives the column and row of one.
+    fn mines(
+        &mut self,
+        size: Vector2<u16>,
+        mines: u16,
+        mut position: impl FnMut(&mut Self) -> Result<(u8, u8), String>,
+    ) -> Result<Board, String> {
+        if size.x == 0 || size.y == 0 {
+            return Err(format!("the {} board is empty", self.format.name()));
+        }
+        if mines as usize > size.x as usize * size.y as usize {
+            return Err(format!(
+                "{} mines don't fit on a {}x{} board",
+                mines, size.x, size.y
+            ));
+        }
+
+        let mut board = Board::new(size);
+        for _ in 0..mines {
+            let (x, y) = position(self)?;
+            let cell = board
+                .get_mut(&Vector2::new(x as u16, y as u16))
+                .ok_or_else(|| {
+                    format!(
+                        "a mine at column {} row {} is outside of the {}x{} board",
+                        x as u16 + 1,
+                        y as u16 + 1,
+                        size.x,
+                        size.y
+                    )
+                })?;
+
+            if cell.value == CellValue::Bomb {
+                return Err(format!(
+                    "two mines at column {} row {}",
+                    x as u16 + 1,
+                    y as u16 + 1
+                ));
+            }
+            cell.value = CellValue::Bomb;
+        }
+
+        board.update_bombs_around();
+        Ok(board)
+    }
+
+    fn take(&mut self, count: usize) -> Result<&[u8], String> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + count)
+            .ok_or_else(|| format!("the {} file ends early", self.format.name()))?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), String> {
+        self.take(count).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        if self.position == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "the {} file has {} bytes after the board",
+                self.format.name(),
+                self.bytes.len() - self.position
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mine_positions(board: &Board) -> Vec<(u16, u16)> {
+        board
+            .positions()
+            .filter(|p| board[*p].value == CellValue::Bomb)
+            .map(|p| (p.x, p.y))
+            .collect()
+    }
+
+    #[test]
+    fn mbf() {
+        let board = parse(Format::Mbf, &[5, 4, 0, 2, 4, 0, 1, 3]).unwrap();
+
+        assert_eq!(board.size(), Vector2::new(5, 4));
+        assert_eq!(mine_positions(&board), vec![(4, 0), (1, 3)]);
+
+        assert_eq!(
+            parse(Format::Mbf, &[5, 4, 0, 2, 4, 0]),
+            Err("the MBF file ends early".to_string())
+        );
+        assert_eq!(
+            parse(Format::Mbf, &[5, 4, 0, 1, 5, 0]),
+            Err("a mine at column 6 row 1 is outside of the 5x4 board".to_string())
+        );
+        assert!(parse(Format::Mbf, &[5, 4, 0, 2, 1, 1, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn avf() {
+        let mut bytes = vec![0; 5];
+        bytes.extend([6, 4, 3, 0, 2, 1, 1, 4, 5]);
+        let board = parse(Format::Avf, &bytes).unwrap();
+
+        assert_eq!(board.size(), Vector2::new(5, 4));
+        assert_eq!(mine_positions(&board), vec![(0, 0), (4, 3)]);
+
+        assert_eq!(
+            parse(Format::Avf, &[0, 0, 0, 0, 0, 3, 1, 1]),
+            Err("the AVF file ends early".to_string())
+        );
+        bytes[5] = 7;
+        assert!(parse(Format::Avf, &bytes)
+            .unwrap_err()
+            .starts_with("AVF mode 7 is not supported"));
+    }
+
+    #[test]
+    fn rmv() {
+        let mut bytes = b"*rmv".to_vec();
+        bytes.extend([0, 1, 0, 2, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        bytes.extend(b"okx");
+        bytes.extend([4, 4, 0, 1, 2, 3]);
+        let board = parse(Format::Rmv, &bytes).unwrap();
+
+        assert_eq!(mine_positions(&board), vec![(2, 3)]);
+
+        // the header says the board section is 7 bytes, it has 6
+        let mut longer = bytes.clone();
+        longer[13] = 7;
+        assert_eq!(
+            parse(Format::Rmv, &longer),
+            Err(
+                "the board section of the RMV file should be 7 bytes long but is 6, this \
+                 variant is not supported"
+                    .to_string()
+            )
+        );
+
+        bytes[5] = 2;
+        assert_eq!(
+            parse(Format::Rmv, &bytes),
+            Err("RMV file type 2 is not supported, only 1 is".to_string())
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game_types::{Cell, CellState, CellValue},
+};
+
+// --- Plain-text board format --- //
+
+// minesweeper-board 1
+// size 5 3
+// mines 4
+//
+// ..*F.
+// .13f?
+// .01*!
+//
+// .  closed cell          *  closed mine
+// F  flagged mine         f  flagged cell without a mine
+// ?  question mark        !  question mark on a mine
+// 0  to 8, opened cell    X  opened mine, the game is lost
+//
+// Numbers have to match the mines around them, so a board can't be shared with wrong counts.
+
+const HEADER: &str = "minesweeper-board 1";
+
+/// Reads a board with its header, e.g. from a file someone shared.
+pub fn parse(text: &str) -> Result<Board, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    if lines.next() != Some(HEADER) {
+        return Err("not a minesweeper board".to_string());
+    }
+
+    let mut size = None;
+    let mut mines = None;
+    let mut rows = Vec::new();
+
+    for line in lines {
+        let mut words = line.split_whitespace();
+
+        match (words.next(), words.next(), words.next(), words.next()) {
+            (Some("size"), Some(x), Some(y), None) => {
+                size = Some((parse_value::<u16>(x)?, parse_value::<u16>(y)?))
+            }
+            (Some("mines"), Some(count), None, None) => mines = Some(parse_value::<usize>(count)?),
+            _ => rows.push(line),
+        }
+    }
+
+    let (width, height) = size.ok_or("missing size")?;
+    let mines = mines.ok_or("missing mines")?;
+
+    if rows.len() != height as usize {
+        return Err(format!("{} rows, the size says {}", rows.len(), height));
+    }
+    if let Some((y, row)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.chars().count() != width as usize)
+    {
+        return Err(format!(
+            "row {} has {} cells, the size says {}",
+            y + 1,
+            row.chars().count(),
+            width
+        ));
+    }
+
+    let board = parse_rows(&rows)?;
+    let found = board
+        .cells()
+        .filter(|cell| cell.value == CellValue::Bomb)
+        .count();
+    if found != mines {
+        return Err(format!(
+            "{} mines on the board, the header says {}",
+            found, mines
+        ));
+    }
+
+    Ok(board)
+}
+
+/// Reads the rows of a board, all as wide as the first one.
+pub fn parse_rows(rows: &[&str]) -> Result<Board, String> {
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if width == 0 || rows.iter().any(|row| row.chars().count() != width) {
+        return Err("rows have to be the same width and not empty".to_string());
+    }
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(rows.len())) else {
+        return Err(format!("the board can't be bigger than {0}x{0}", u16::MAX));
+    };
+
+    let mut board = Board::new(Vector2::new(width, height));
+    let mut numbers = Vec::new();
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, symbol) in row.chars().enumerate() {
+            let position = Vector2::new(x as u16, y as u16);
+            let cell = &mut board[position];
+
+            (cell.state, cell.value) = match symbol {
+                '.' => (CellState::Closed, CellValue::Empty),
+                '*' => (CellState::Closed, CellValue::Bomb),
+                'F' => (CellState::Flagged, CellValue::Bomb),
+                'f' => (CellState::Flagged, CellValue::Empty),
+                '?' => (CellState::Questioned, CellValue::Empty),
+                '!' => (CellState::Questioned, CellValue::Bomb),
+                'X' => (CellState::Opened, CellValue::Bomb),
+                '0'..='8' => {
+                    numbers.push((position, symbol as u8 - b'0'));
+                    (CellState::Opened, CellValue::Empty)
+                }
+                _ => {
+                    return Err(format!(
+                        "unknown cell `{}` at row {} column {}",
+                        symbol,
+                        y + 1,
+                        x + 1
+                    ))
+                }
+            };
+        }
+    }
+
+    board.update_bombs_around();
+
+    for (position, number) in numbers {
+        let around = board[position].bombs_around;
+        if around != number {
+            return Err(format!(
+                "the {} at row {} column {} should be a {}",
+                number,
+                position.y + 1,
+                position.x + 1,
+                around
+            ));
+        }
+    }
+
+    Ok(board)
+}
+
+/// Writes the whole board with its header, mines included.
+pub fn write(board: &Board) -> String {
+    let size = board.size();
+    let mines = board
+        .cells()
+        .filter(|cell| cell.value == CellValue::Bomb)
+        .count();
+
+    let mut text = format!(
+        "{}\nsize {} {}\nmines {}\n\n",
+        HEADER, size.x, size.y, mines
+    );
+    for row in rows(board) {
+        text.push_str(&row);
+        text.push('\n');
+    }
+
+    text
+}
+
+/// The rows of the board in the text format, without the header.
+pub fn rows(board: &Board) -> impl Iterator<Item = String> + '_ {
+    board.rows().map(|row| row.iter().map(symbol).collect())
+}
+
+fn symbol(cell: &Cell) -> char {
+    match (cell.state, cell.value) {
+        (CellState::Closed, CellValue::Empty) => '.',
+        (CellState::Closed, CellValue::Bomb) => '*',
+        (CellState::Flagged, CellValue::Bomb) => 'F',
+        (CellState::Flagged, CellValue::Empty) => 'f',
+        (CellState::Questioned, CellValue::Empty) => '?',
+        (CellState::Questioned, CellValue::Bomb) => '!',
+        (CellState::Opened, CellValue::Bomb) => 'X',
+        (CellState::Opened, CellValue::Empty) => {
+            char::from_digit(cell.bombs_around as u32, 10).unwrap_or('.')
+        }
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}`", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "minesweeper-board 1\nsize 5 3\nmines 4\n\n..*F.\n.13f?\n.01*!\n";
+
+    #[test]
+    fn round_trip() {
+        let board = parse(BOARD).unwrap();
+
+        assert_eq!(board[Vector2::new(3, 0)].state, CellState::Flagged);
+        assert_eq!(board[Vector2::new(2, 1)].bombs_around, 3);
+        assert_eq!(write(&board), BOARD);
+    }
+
+    #[test]
+    fn rejects_wrong_boards() {
+        assert_eq!(
+            parse("size 5 3"),
+            Err("not a minesweeper board".to_string())
+        );
+        assert_eq!(
+            parse(&BOARD.replace("mines 4", "mines 5")),
+            Err("4 mines on the board, the header says 5".to_string())
+        );
+        assert_eq!(
+            parse(&BOARD.replace(".13f?", ".12f?")),
+            Err("the 2 at row 2 column 3 should be a 3".to_string())
+        );
+        assert_eq!(
+            parse(&BOARD.replace(".01*!", ".01*")),
+            Err("row 3 has 4 cells, the size says 5".to_string())
+        );
+        assert!(parse(&BOARD.replace('?', "#")).is_err());
+
+        let wide = ".".repeat(70_000);
+        assert_eq!(
+            parse_rows(&[&wide]),
+            Err("the board can't be bigger than 65535x65535".to_string())
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    cell::RefCell,
+    fs,
+    io::{Result, Write},
+    rc::Rc,
+    time::Duration,
+};
+
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal, TerminalOptions, Viewport};
+use serde_json::json;
+
+use crate::{
+    cli::{CastArgs, Cli},
+    config::Config,
+    constants::*,
+    replay::Replay,
+    stopwatch::Stopwatch,
+    App, AppState,
+};
+
+// --- asciicast v2 --- //
+
+// {"version": 2, "width": 37, "height": 19, "title": "minesweeper-42"}
+// [0.0, "o", "\u001b[1;1H..."]
+// [1.52, "o", "..."]
+//
+// A header line, then one event per line with the seconds since the start and what the game
+// wrote to the terminal. See https://docs.asciinema.org/manual/asciicast/v2/.
+
+/// How long the final board stays on screen at the end of the cast.
+const FINAL_FRAME: Duration = Duration::from_secs(3);
+
+/// Writes the replay as an asciicast, drawn by the game into an in-memory terminal.
+pub(crate) fn run(cli: &Cli, args: &CastArgs) -> Result<()> {
+    let replay = Replay::load(&args.replay)?;
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| args.replay.with_extension("cast"));
+
+    // the recording looks the same for everyone, only the theme can be picked on the command line
+    let mut app = App::new(cli, &Config::default());
+    app.screen_reader = false;
+    let size = board_terminal_size(&replay);
+    let columns = args.columns.unwrap_or(size.width);
+    let rows = args.rows.unwrap_or(size.height);
+
+    fs::write(&output, cast(app, &replay, Rect::new(0, 0, columns, rows))?)?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+/// A terminal that shows the whole board and the status lines below it.
+fn board_terminal_size(replay: &Replay) -> Rect {
+    let size = replay.game_settings.size;
+
+    Rect::new(
+        0,
+        0,
+        (size.x * (CELL_WIDTH - 1) + 1).max(40),
+        size.y * (CELL_HEIGHT - 1) + 1 + STATUS_HEIGHT,
+    )
+}
+
+/// What the terminal wrote since the last frame was taken out.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, bytes: &[u8]) -> Result<usize> {
+        self.0.borrow_mut().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn cast(mut app: App, replay: &Replay, area: Rect) -> Result<String> {
+    let output = Output::default();
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(output.clone()),
+        TerminalOptions {
+            viewport: Viewport::Fixed(area),
+        },
+    )?;
+    terminal.clear()?;
+
+    let mut cast = json!({
+        "version": 2,
+        "width": area.width,
+        "height": area.height,
+        "title": format!("minesweeper-{}", replay.seed),
+    })
+    .to_string();
+    cast.push('\n');
+
+    // the board before the first move, the moves are played below at their own time
+    app.start_replay(Replay {
+        moves: Vec::new(),
+        ..replay.clone()
+    });
+    app.status_message = None;
+    write_frame(&mut cast, &mut terminal, &output, &mut app, Duration::ZERO)?;
+
+    let mut time = Duration::ZERO;
+    for m in &replay.moves {
+        time = Duration::from_millis(m.time_ms);
+        app.stopwatch = Stopwatch::with_elapsed(time);
+        app.cursor = m.position;
+        app.apply_move(m);
+        // the cast ends on the board, the game-over panel is only for playing
+        app.state = AppState::Replaying;
+
+        write_frame(&mut cast, &mut terminal, &output, &mut app, time)?;
+    }
+
+    let event = json!([(time + FINAL_FRAME).as_secs_f64(), "o", ""]);
+    cast.push_str(&event.to_string());
+    cast.push('\n');
+
+    Ok(cast)
+}
+
+/// Draws the game and adds what changed on the terminal as an event at `time`.
+fn write_frame(
+    cast: &mut String,
+    terminal: &mut Terminal<CrosstermBackend<Output>>,
+    output: &Output,
+    app: &mut App,
+    time: Duration,
+) -> Result<()> {
+    terminal.draw(|frame| app.render(frame))?;
+
+    let bytes = std::mem::take(&mut *output.0.borrow_mut());
+    let event = json!([time.as_secs_f64(), "o", String::from_utf8_lossy(&bytes)]);
+    cast.push_str(&event.to_string());
+    cast.push('\n');
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use vctr2::vector2::Vector2;
+
+    use super::*;
+    use crate::replay::{Move, MoveKind};
+
+    #[test]
+    fn one_event_per_move() {
+        let replay = Replay {
+            game_settings: DIFFICULY_BEGINNER,
+            seed: 1,
+            no_guess: false,
+            question_marks: false,
+            layout: None,
+            moves: vec![
+                Move {
+                    kind: MoveKind::Open,
+                    position: Vector2::new(4, 4),
+                    time_ms: 0,
+                },
+                Move {
+                    kind: MoveKind::Flag,
+                    position: Vector2::new(0, 0),
+                    time_ms: 1500,
+                },
+            ],
+        };
+        let app = App::new(&Cli::parse_from(["minesweeper"]), &Config::default());
+
+        let cast = cast(app, &replay, board_terminal_size(&replay)).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            r#"{"height":21,"title":"minesweeper-1","version":2,"width":40}"#
+        );
+        // the empty board, two moves and the pause at the end
+        assert_eq!(lines.len(), 1 + 4);
+        assert!(lines[3].starts_with("[1.5,\"o\","));
+        assert_eq!(lines[4], r#"[4.5,"o",""]"#);
+    }
+}
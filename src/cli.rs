@@ -0,0 +1,220 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use vctr2::vector2::Vector2;
+
+use crate::{constants::*, game_types::GameSettings, theme::ThemeName};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn game_settings(self) -> GameSettings {
+        match self {
+            Difficulty::Beginner => DIFFICULY_BEGINNER,
+            Difficulty::Intermediate => DIFFICULY_INTERMEDIATE,
+            Difficulty::Expert => DIFFICULY_EXPERT,
+        }
+    }
+}
+
+/// Simple minesweeper in terminal
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Start a game of this difficulty right away
+    #[arg(short, long, value_enum)]
+    pub difficulty: Option<Difficulty>,
+
+    /// Board width, overrides the difficulty
+    #[arg(long, value_parser = clap::value_parser!(u16).range(MIN_BOARD_SIZE as i64..=MAX_BOARD_SIZE as i64))]
+    pub width: Option<u16>,
+
+    /// Board height, overrides the difficulty
+    #[arg(long, value_parser = clap::value_parser!(u16).range(MIN_BOARD_SIZE as i64..=MAX_BOARD_SIZE as i64))]
+    pub height: Option<u16>,
+
+    /// Number of mines, overrides the difficulty
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..))]
+    pub mines: Option<i32>,
+
+    /// Seed for the mine layout, random when omitted. Each new game adds one to it
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Only generate boards that can be cleared without guessing
+    #[arg(long)]
+    pub no_guess: bool,
+
+    /// Continue a saved game
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "difficulty", "width", "height", "mines", "seed", "no_guess"])]
+    pub load: Option<PathBuf>,
+
+    /// Play a board from a text file, as printed with B during a game, or the mines of an .mbf,
+    /// .avf or .rmv file
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["load", "replay", "difficulty", "width", "height", "mines", "seed", "no_guess"])]
+    pub board: Option<PathBuf>,
+
+    /// Colour theme, overrides the config file
+    #[arg(short, long, value_enum)]
+    pub theme: Option<ThemeName>,
+
+    /// Describe the board in lines of text for screen readers instead of drawing it
+    #[arg(long)]
+    pub screen_reader: bool,
+
+    /// Watch a recorded game
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["difficulty", "width", "height", "mines", "seed", "no_guess"])]
+    pub replay: Option<PathBuf>,
+
+    /// Play through JSON lines on stdin and stdout instead of the terminal, for bots
+    #[arg(long, conflicts_with_all = ["load", "replay", "board", "screen_reader"])]
+    pub stdio: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Let a bot play many seeded boards and compare how well it does
+    Bench(BenchArgs),
+    /// Turn a save or replay into an asciicast v2 file for asciinema
+    Cast(CastArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Games per difficulty
+    #[arg(short, long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+    pub games: u32,
+
+    /// Difficulties to play, all of them when omitted
+    #[arg(short, long, value_enum, num_args = 1..)]
+    pub difficulty: Vec<Difficulty>,
+
+    /// Seed of the first game, the following games count up from it
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Only play boards that can be cleared without guessing
+    #[arg(long)]
+    pub no_guess: bool,
+
+    /// Command of a bot speaking the --stdio protocol, the built-in solver plays when omitted
+    #[arg(long, value_name = "COMMAND")]
+    pub bot: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CastArgs {
+    /// Save or replay file to play
+    #[arg(value_name = "FILE")]
+    pub replay: PathBuf,
+
+    /// Where to write the cast, the replay file with a .cast extension when omitted
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Columns of the recorded terminal, wide enough for the whole board when omitted
+    #[arg(long, value_parser = clap::value_parser!(u16).range(20..))]
+    pub columns: Option<u16>,
+
+    /// Rows of the recorded terminal, high enough for the whole board when omitted
+    #[arg(long, value_parser = clap::value_parser!(u16).range(10..))]
+    pub rows: Option<u16>,
+}
+
+impl Cli {
+    /// Settings of the game to start without the menu, `None` when the menu should be shown.
+    pub fn game_settings(&self) -> Result<Option<GameSettings>, String> {
+        if self.difficulty.is_none()
+            && self.width.is_none()
+            && self.height.is_none()
+            && self.mines.is_none()
+        {
+            return Ok(None);
+        }
+
+        let base = self
+            .difficulty
+            .unwrap_or(Difficulty::Beginner)
+            .game_settings();
+
+        let game_settings = GameSettings {
+            size: Vector2::new(
+                self.width.unwrap_or(base.size.x),
+                self.height.unwrap_or(base.size.y),
+            ),
+            mines: self.mines.unwrap_or(base.mines),
+        };
+
+        check_game_settings(&game_settings)?;
+        Ok(Some(game_settings))
+    }
+}
+
+/// Checks that a custom board can be played, the error tells what is wrong with it.
+pub fn check_game_settings(game_settings: &GameSettings) -> Result<(), String> {
+    let size = game_settings.size;
+    check_board_size(size)?;
+
+    // the first opened cell and its neighbours never have a mine
+    let max_mines = size.x as i32 * size.y as i32 - 9;
+    if !(1..=max_mines).contains(&game_settings.mines) {
+        return Err(format!(
+            "a {}x{} board fits 1 to {} mines",
+            size.x, size.y, max_mines
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn check_board_size(size: Vector2<u16>) -> Result<(), String> {
+    if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size.x)
+        || !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size.y)
+    {
+        return Err(format!(
+            "width and height have to be between {} and {}",
+            MIN_BOARD_SIZE, MAX_BOARD_SIZE
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_settings() {
+        let cli = Cli::parse_from([
+            "minesweeper",
+            "--width",
+            "30",
+            "--height",
+            "16",
+            "--mines",
+            "99",
+        ]);
+        assert_eq!(
+            cli.game_settings(),
+            Ok(Some(GameSettings {
+                size: Vector2::new(30, 16),
+                mines: 99
+            }))
+        );
+
+        let cli = Cli::parse_from(["minesweeper", "--seed", "5"]);
+        assert_eq!(cli.game_settings(), Ok(None));
+
+        let cli = Cli::parse_from(["minesweeper", "--width", "4", "--height", "4"]);
+        assert!(cli.game_settings().is_err());
+    }
+}
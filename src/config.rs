@@ -0,0 +1,86 @@
+use std::{
+    env, fs,
+    io::{Error, ErrorKind, Result},
+    path::PathBuf,
+};
+
+use serde::Deserialize;
+
+use crate::theme::ThemeName;
+
+// --- Config file --- //
+
+// Read from `$XDG_CONFIG_HOME/minesweeper/config.toml` (or `~/.config/minesweeper/config.toml`):
+//
+// theme = "dark"
+// screen-reader = false
+// question-marks = true
+//
+// Without a theme here or on the command line, `NO_COLOR` switches to the symbols only theme.
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub theme: Option<ThemeName>,
+    pub screen_reader: bool,
+    /// Flagging a flagged cell marks it with a question mark before closing it again
+    pub question_marks: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            screen_reader: false,
+            question_marks: true,
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_dir.join("minesweeper").join("config.toml"))
+    }
+
+    /// Loads the config file, a missing file gives the default config.
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|error| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), error),
+                )
+            }),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn parse(text: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(
+            Config::parse("theme = \"high-contrast\"").unwrap().theme,
+            Some(ThemeName::HighContrast)
+        );
+        assert!(Config::parse("theme = \"rainbow\"").is_err());
+    }
+}
@@ -28,3 +28,46 @@ pub(crate) const DIFFICULY_EXPERT: GameSettings = GameSettings {
     size: Vector2::new(15, 15),
     mines: 30,
 };
+
+/// The difficulties of the first entries of the menu.
+pub(crate) const DIFFICULTIES: [GameSettings; 3] =
+    [DIFFICULY_BEGINNER, DIFFICULY_INTERMEDIATE, DIFFICULY_EXPERT];
+
+pub(crate) const MENU_OPTIONS: [&str; 6] = [
+    "Beginner",
+    "Intermediate",
+    "Expert",
+    "Watch the solver",
+    "Tutorial",
+    "Puzzle trainer",
+];
+
+/// Keys of the panel shown when a game is over.
+pub(crate) const GAME_OVER_CHOICES: [(&str, &str); 4] = [
+    ("A", "same mines again"),
+    ("N", "new game, same difficulty"),
+    ("V", "watch the replay"),
+    ("Esc", "back to the menu"),
+];
+
+// --- Custom boards --- //
+
+pub(crate) const MIN_BOARD_SIZE: u16 = 4;
+pub(crate) const MAX_BOARD_SIZE: u16 = 1000;
+
+// --- Layout --- //
+
+pub(crate) const MENU_X: u16 = 3;
+pub(crate) const MENU_Y: u16 = 1;
+pub(crate) const MENU_WIDTH: u16 = 31;
+pub(crate) const MENU_HEIGHT: u16 = 10;
+
+/// Panel next to the board with the lesson or the puzzle.
+pub(crate) const SIDE_PANEL_WIDTH: u16 = 34;
+pub(crate) const SIDE_PANEL_HEIGHT: u16 = 14;
+
+/// Lines below the board, for the timer and the status message.
+pub(crate) const STATUS_HEIGHT: u16 = 2;
+
+/// Boards bigger than the terminal scroll, but at least this many cells have to fit each way.
+pub(crate) const MIN_VISIBLE_CELLS: u16 = 5;
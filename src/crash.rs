@@ -0,0 +1,104 @@
+use std::{
+    backtrace::Backtrace,
+    fs,
+    io::{stdout, Result},
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use ratatui::crossterm::{
+    cursor::Show,
+    event::{DisableFocusChange, EnableFocusChange},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+
+use crate::replay::{Move, Replay};
+
+/// The game being played, written into the crash report so the crash can be replayed.
+static GAME: Mutex<Option<Replay>> = Mutex::new(None);
+
+/// Keeps the terminal in raw mode on the alternate screen until it is dropped,
+/// also when `main` returns early with an error.
+pub(crate) struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> Result<Self> {
+        stdout().execute(EnterAlternateScreen)?;
+        stdout().execute(EnableFocusChange)?;
+        enable_raw_mode()?;
+
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    // the terminal is restored as far as possible, errors can't be reported anywhere
+    let _ = stdout().execute(Show);
+    let _ = stdout().execute(DisableFocusChange);
+    let _ = stdout().execute(LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+}
+
+/// Restores the terminal before a panic message is printed and writes a crash report.
+pub(crate) fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+
+        match write_report(info) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(error) => eprintln!("Could not write crash report: {}", error),
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// Remembers the game for the crash report, to be called when a game starts.
+pub(crate) fn set_game(replay: Replay) {
+    // never wait here, the lock could only be held by a panicking thread
+    if let Ok(mut game) = GAME.try_lock() {
+        *game = Some(replay);
+    }
+}
+
+/// Adds a move of the player to the game of the crash report.
+pub(crate) fn add_move(m: Move) {
+    if let Ok(mut game) = GAME.try_lock() {
+        if let Some(replay) = game.as_mut() {
+            replay.moves.push(m);
+        }
+    }
+}
+
+fn write_report(info: &PanicHookInfo) -> Result<PathBuf> {
+    let game = GAME.try_lock().ok().and_then(|game| game.clone());
+    let path = PathBuf::from(match &game {
+        Some(replay) => format!("minesweeper-crash-{}.txt", replay.seed),
+        None => "minesweeper-crash.txt".to_string(),
+    });
+
+    let mut report = format!(
+        "minesweeper {} crashed: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        info,
+        Backtrace::force_capture()
+    );
+
+    if let Some(replay) = game {
+        report.push_str("\nReplay, save it as a file and pass it to --replay:\n\n");
+        report.push_str(&replay.to_string());
+    }
+
+    fs::write(&path, report)?;
+    Ok(path)
+}
@@ -0,0 +1,200 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game::Game,
+    game_types::CellState,
+    solver::{self, Action, Deduction, Technique},
+};
+
+const MIN_DELAY: Duration = Duration::from_millis(50);
+const MAX_DELAY: Duration = Duration::from_millis(3200);
+
+/// How long a finished board stays on screen before the next one starts.
+pub(crate) const FINISHED_DELAY: Duration = Duration::from_secs(3);
+
+/// The built-in solver playing a board one step at a time, explaining every step.
+pub(crate) struct Demo {
+    pub delay: Duration,
+    pub last_step: Instant,
+    /// When the board was solved or lost, the next board starts [`FINISHED_DELAY`] later.
+    pub finished_at: Option<Instant>,
+    /// Numbers the last step was deduced from, drawn highlighted.
+    pub sources: Vec<Vector2<u16>>,
+    /// Cell opened or flagged by the last step.
+    pub target: Option<Vector2<u16>>,
+    rng: StdRng,
+}
+
+impl Demo {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            delay: Duration::from_millis(400),
+            last_step: Instant::now(),
+            finished_at: None,
+            sources: Vec::new(),
+            target: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.delay = (self.delay / 2).max(MIN_DELAY);
+    }
+
+    pub fn slower(&mut self) {
+        self.delay = (self.delay * 2).min(MAX_DELAY);
+    }
+
+    /// Time until the next step is due.
+    pub fn next_step_in(&self) -> Duration {
+        match self.finished_at {
+            Some(finished_at) => FINISHED_DELAY.saturating_sub(finished_at.elapsed()),
+            None => self.delay.saturating_sub(self.last_step.elapsed()),
+        }
+    }
+
+    /// Plays the next move on the game and returns why it was made.
+    pub fn step(&mut self, game: &mut Game) -> String {
+        self.last_step = Instant::now();
+        self.sources.clear();
+
+        let size = game.settings().size;
+        if !game.mines_placed() {
+            let start = Vector2::new(size.x / 2, size.y / 2);
+            self.target = Some(start);
+            game.reveal(&start);
+
+            return format!(
+                "The first cell is always safe, opening {}.",
+                cell_name(&start)
+            );
+        }
+
+        let board = game.visible_board();
+        let Some(deduction) = solver::find_deductions(&board).first().copied() else {
+            let unknown: Vec<Vector2<u16>> = board
+                .positions()
+                .filter(|p| matches!(board[*p].state, CellState::Closed | CellState::Questioned))
+                .collect();
+            let guess = unknown[self.rng.gen_range(0..unknown.len())];
+            self.target = Some(guess);
+            game.reveal(&guess);
+
+            return format!(
+                "Nothing follows from the numbers, guessing {}.",
+                cell_name(&guess)
+            );
+        };
+
+        self.target = Some(deduction.position);
+        self.sources.push(deduction.source);
+        self.sources.extend(deduction.subset);
+
+        match deduction.action {
+            Action::Open => {
+                game.reveal(&deduction.position);
+            }
+            Action::Flag => {
+                game.flag(&deduction.position);
+            }
+        }
+
+        explain(&board, &deduction)
+    }
+}
+
+/// Explains a deduction in a sentence, e.g. "The 1 at row 2 column 3 already has all its mines
+/// flagged, so row 3 column 4 is safe."
+pub(crate) fn explain(board: &Board, deduction: &Deduction) -> String {
+    let source = number_name(board, &deduction.source);
+    let conclusion = match deduction.action {
+        Action::Open => format!("{} is safe", cell_name(&deduction.position)),
+        Action::Flag => format!("{} is a mine", cell_name(&deduction.position)),
+    };
+
+    match (deduction.technique, deduction.subset) {
+        (Technique::Basic, _) => match deduction.action {
+            Action::Open => format!(
+                "{} already has all its mines flagged, so {}.",
+                source, conclusion
+            ),
+            Action::Flag => format!(
+                "{} has as many closed neighbours as missing mines, so {}.",
+                source, conclusion
+            ),
+        },
+        (Technique::Subset, Some(subset)) => format!(
+            "{} has all its closed neighbours next to {} too, so {}.",
+            number_name(board, &subset),
+            number_name(board, &deduction.source).to_lowercase(),
+            conclusion
+        ),
+        (Technique::Subset, None) => format!("{} decides that {}.", source, conclusion),
+        (Technique::Global, _) => match deduction.action {
+            Action::Open => format!(
+                "The numbers already account for every mine left on the board, so {}.",
+                conclusion
+            ),
+            Action::Flag => format!(
+                "There are as many closed cells left as mines, so {}.",
+                conclusion
+            ),
+        },
+    }
+}
+
+/// Rows and columns are counted from 1, like the screen reader texts.
+fn cell_name(position: &Vector2<u16>) -> String {
+    format!("row {} column {}", position.y + 1, position.x + 1)
+}
+
+fn number_name(board: &Board, position: &Vector2<u16>) -> String {
+    format!(
+        "The {} at {}",
+        board[*position].bombs_around,
+        cell_name(position)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::DIFFICULY_BEGINNER, game::GameStatus};
+
+    #[test]
+    fn plays_until_over() {
+        let mut game = Game::new(DIFFICULY_BEGINNER, 3);
+        let mut demo = Demo::new(3);
+
+        assert_eq!(
+            demo.step(&mut game),
+            "The first cell is always safe, opening row 5 column 5."
+        );
+
+        for _ in 0..200 {
+            if game.status() != GameStatus::Playing {
+                break;
+            }
+            demo.step(&mut game);
+        }
+        assert_ne!(game.status(), GameStatus::Playing);
+    }
+
+    #[test]
+    fn explains_basic_flag() {
+        let mut board = Board::new(Vector2::new(2, 1));
+        board[Vector2::new(1, 0)].value = crate::game_types::CellValue::Bomb;
+        board[Vector2::new(0, 0)].state = CellState::Opened;
+        board.update_bombs_around();
+
+        let deduction = solver::find_deductions(&board)[0];
+        assert_eq!(
+            explain(&board, &deduction),
+            "The 1 at row 1 column 1 has as many closed neighbours as missing mines, so row 1 column 2 is a mine."
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    board_text,
+    game_types::{CellState, CellValue, GameSettings},
+    game_utils::{get_neighbours, open_cell},
+    solver,
+};
+
+/// How many layouts are tried before giving up on finding one that needs no guessing.
+pub const NO_GUESS_ATTEMPTS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevealOutcome {
+    /// The newly opened cells, starting with the revealed one.
+    Opened(Vec<Vector2<u16>>),
+    /// A mine was revealed at the position, the game is lost.
+    Exploded(Vector2<u16>),
+    /// Nothing changed: the cell is outside, opened or flagged, or the game is over.
+    Ignored,
+}
+
+/// A single game, playable without a user interface.
+///
+/// The mines are placed on the first reveal, never next to it, from a generator seeded with
+/// `seed`: the same seed and first reveal always give the same layout. While the game is
+/// running, only [`Game::visible_board`] is exposed, so bots can't peek at closed cells.
+pub struct Game {
+    settings: GameSettings,
+    seed: u64,
+    no_guess: bool,
+    question_marks: bool,
+    board: Board,
+    mines_placed: bool,
+    /// Set when no layout without guessing was found in [`NO_GUESS_ATTEMPTS`] tries.
+    needs_guessing: bool,
+    status: GameStatus,
+    /// The mine that was revealed and lost the game.
+    exploded: Option<Vector2<u16>>,
+    first_reveal: Option<Vector2<u16>>,
+    /// Cells without a mine that are still closed, the game is won when none are left.
+    safe_left: usize,
+}
+
+impl Game {
+    /// A new game, the mines are cut down to what fits next to the first reveal: every cell but
+    /// the revealed one and its neighbours.
+    pub fn new(mut settings: GameSettings, seed: u64) -> Self {
+        let cells = settings.size.x as usize * settings.size.y as usize;
+        settings.mines = settings.mines.clamp(0, cells.saturating_sub(9) as i32);
+
+        Self::with_settings(settings, seed)
+    }
+
+    /// A new game with the mines of `settings` as they are, they have to fit on the board.
+    fn with_settings(settings: GameSettings, seed: u64) -> Self {
+        Self {
+            settings,
+            seed,
+            no_guess: false,
+            question_marks: false,
+            board: Board::new(settings.size),
+            mines_placed: false,
+            needs_guessing: false,
+            status: GameStatus::Playing,
+            exploded: None,
+            first_reveal: None,
+            safe_left: settings.size.x as usize * settings.size.y as usize
+                - settings.mines as usize,
+        }
+    }
+
+    /// A game on a fixed layout, e.g. one from [`board_text`], keeping its opened and flagged
+    /// cells. A board with an opened mine is lost already.
+    pub fn from_board(mut board: Board) -> Self {
+        board.update_bombs_around();
+
+        // the layout is fixed, so the mines don't have to leave room around a first reveal
+        let mut game = Self::with_settings(
+            GameSettings {
+                size: board.size(),
+                mines: board
+                    .cells()
+                    .filter(|cell| cell.value == CellValue::Bomb)
+                    .count() as i32,
+            },
+            0,
+        );
+        game.safe_left = board
+            .cells()
+            .filter(|cell| cell.value == CellValue::Empty && cell.state != CellState::Opened)
+            .count();
+        game.mines_placed = true;
+
+        game.exploded = board.positions().find(|position| {
+            board[*position].value == CellValue::Bomb && board[*position].state == CellState::Opened
+        });
+        game.board = board;
+
+        if game.exploded.is_some() {
+            game.lose();
+        } else if game.safe_left == 0 {
+            game.status = GameStatus::Won;
+        }
+
+        game
+    }
+
+    /// Only place mines so that the board can be cleared without guessing.
+    pub fn with_no_guess(mut self, no_guess: bool) -> Self {
+        self.no_guess = no_guess;
+        self
+    }
+
+    /// Flagging a flagged cell marks it with a question mark instead of closing it.
+    pub fn with_question_marks(mut self, question_marks: bool) -> Self {
+        self.question_marks = question_marks;
+        self
+    }
+
+    pub fn settings(&self) -> GameSettings {
+        self.settings
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// The mine that lost the game, `None` when the player resigned or no mine was revealed.
+    pub fn exploded(&self) -> Option<Vector2<u16>> {
+        self.exploded
+    }
+
+    /// Whether the first cell was revealed and the mines are placed.
+    pub fn mines_placed(&self) -> bool {
+        self.mines_placed
+    }
+
+    /// The cell the mines were placed around, `None` before the first reveal and for games on a
+    /// fixed layout.
+    pub fn first_reveal(&self) -> Option<Vector2<u16>> {
+        self.first_reveal
+    }
+
+    /// Whether no-guess generation gave up, so the board may need a guess after all.
+    pub fn needs_guessing(&self) -> bool {
+        self.needs_guessing
+    }
+
+    /// The board as the player sees it.
+    ///
+    /// While playing, closed, flagged and questioned cells never tell whether they hold a mine
+    /// and have no mine count. Once the game is over, every cell is shown.
+    pub fn visible_board(&self) -> Board {
+        let mut board = self.board.clone();
+
+        if self.status == GameStatus::Playing {
+            for cell in board.cells_mut() {
+                if cell.state != CellState::Opened {
+                    cell.value = CellValue::Empty;
+                    cell.bombs_around = 0;
+                }
+            }
+        }
+
+        board
+    }
+
+    /// The whole board in the format of [`board_text`], mines included, to share the position.
+    /// `None` while the game runs, the mines are only told once it is over.
+    pub fn board_text(&self) -> Option<String> {
+        (self.status != GameStatus::Playing).then(|| board_text::write(&self.board))
+    }
+
+    /// Opens a closed or questioned cell, spreading over cells without mines around.
+    pub fn reveal(&mut self, position: &Vector2<u16>) -> RevealOutcome {
+        if self.status != GameStatus::Playing
+            || !matches!(
+                self.board.get(position).map(|cell| cell.state),
+                Some(CellState::Closed | CellState::Questioned)
+            )
+        {
+            return RevealOutcome::Ignored;
+        }
+
+        if !self.mines_placed {
+            self.place_mines(position);
+        }
+
+        let opened = open_cell(&mut self.board, position);
+
+        if self.board[*position].value == CellValue::Bomb {
+            self.exploded = Some(*position);
+            self.lose();
+            return RevealOutcome::Exploded(*position);
+        }
+
+        self.safe_left -= opened.len();
+        if self.safe_left == 0 {
+            self.status = GameStatus::Won;
+        }
+
+        RevealOutcome::Opened(opened)
+    }
+
+    /// Cycles a closed cell through flagged, questioned (when enabled) and closed again,
+    /// returning its new state.
+    pub fn flag(&mut self, position: &Vector2<u16>) -> Option<CellState> {
+        if self.status != GameStatus::Playing {
+            return None;
+        }
+
+        let question_marks = self.question_marks;
+        let cell = self.board.get_mut(position)?;
+        cell.state = match cell.state {
+            CellState::Closed => CellState::Flagged,
+            CellState::Flagged if question_marks => CellState::Questioned,
+            CellState::Flagged | CellState::Questioned => CellState::Closed,
+            CellState::Opened => CellState::Opened,
+        };
+
+        Some(cell.state)
+    }
+
+    /// Reveals every unflagged neighbour of an opened number whose mines are all flagged.
+    pub fn chord(&mut self, position: &Vector2<u16>) -> RevealOutcome {
+        let Some(cell) = self.board.get(position) else {
+            return RevealOutcome::Ignored;
+        };
+        if self.status != GameStatus::Playing
+            || cell.state != CellState::Opened
+            || cell.bombs_around == 0
+        {
+            return RevealOutcome::Ignored;
+        }
+
+        let neighbours = get_neighbours(&self.board.size(), position);
+        let flagged = neighbours
+            .iter()
+            .filter(|n| self.board[**n].state == CellState::Flagged)
+            .count();
+        if flagged != cell.bombs_around as usize {
+            return RevealOutcome::Ignored;
+        }
+
+        let mut opened = Vec::new();
+        for neighbour in neighbours {
+            match self.reveal(&neighbour) {
+                RevealOutcome::Opened(cells) => opened.extend(cells),
+                RevealOutcome::Exploded(position) => return RevealOutcome::Exploded(position),
+                RevealOutcome::Ignored => {}
+            }
+        }
+
+        if opened.is_empty() {
+            RevealOutcome::Ignored
+        } else {
+            RevealOutcome::Opened(opened)
+        }
+    }
+
+    /// Ends the game as lost, showing every mine.
+    pub fn resign(&mut self) {
+        if self.status == GameStatus::Playing {
+            self.lose();
+        }
+    }
+
+    fn lose(&mut self) {
+        self.status = GameStatus::Lost;
+
+        // flags stay, so that wrong ones can be told apart from the mines that were found
+        for cell in self.board.cells_mut() {
+            if cell.value == CellValue::Bomb && cell.state != CellState::Flagged {
+                cell.state = CellState::Opened;
+            }
+        }
+    }
+
+    fn place_mines(&mut self, start: &Vector2<u16>) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let empty_board = Board::new(self.settings.size);
+        self.mines_placed = true;
+        self.first_reveal = Some(*start);
+
+        for _ in 0..NO_GUESS_ATTEMPTS {
+            let mut board = empty_board.clone();
+            Self::place_random_mines(&mut board, self.settings.mines, start, &mut rng);
+            board.update_bombs_around();
+
+            // checked before the flags are copied, the solver trusts them but they may be wrong
+            let solvable = !self.no_guess || solver::is_solvable(&board, start);
+
+            // keep the flags the player placed before the first reveal
+            for (cell, old_cell) in board.cells_mut().zip(self.board.cells()) {
+                cell.state = old_cell.state;
+            }
+            self.board = board;
+
+            if solvable {
+                return;
+            }
+        }
+
+        self.needs_guessing = true;
+    }
+
+    fn place_random_mines(board: &mut Board, mines: i32, start: &Vector2<u16>, rng: &mut StdRng) {
+        let size = board.size();
+        let mut count = mines;
+        let mut excluded_positions = get_neighbours(&size, start);
+        excluded_positions.push(*start);
+
+        while count > 0 {
+            let position = Vector2::new(rng.gen_range(0..size.x), rng.gen_range(0..size.y));
+
+            if excluded_positions.contains(&position) {
+                continue;
+            }
+
+            if board[position].value != CellValue::Bomb {
+                board[position].value = CellValue::Bomb;
+                count -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: GameSettings = GameSettings {
+        size: Vector2::new(9, 9),
+        mines: 10,
+    };
+
+    #[test]
+    fn hides_mines_until_over() {
+        let mut game = Game::new(SETTINGS, 42);
+
+        assert!(matches!(
+            game.reveal(&Vector2::new(4, 4)),
+            RevealOutcome::Opened(cells) if cells[0] == Vector2::new(4, 4)
+        ));
+        assert!(game
+            .visible_board()
+            .cells()
+            .all(|cell| cell.value == CellValue::Empty));
+        assert_eq!(game.board_text(), None);
+
+        game.resign();
+        assert_eq!(game.status(), GameStatus::Lost);
+        assert_eq!(
+            game.visible_board()
+                .cells()
+                .filter(|cell| cell.value == CellValue::Bomb && cell.state == CellState::Opened)
+                .count(),
+            10
+        );
+        assert_eq!(game.reveal(&Vector2::new(0, 0)), RevealOutcome::Ignored);
+    }
+
+    #[test]
+    fn no_guess_ignores_early_flags() {
+        let mut game = Game::new(SETTINGS, 3).with_no_guess(true);
+        for x in 0..9 {
+            game.flag(&Vector2::new(x, 8));
+        }
+        game.reveal(&Vector2::new(4, 4));
+
+        assert!(!game.needs_guessing());
+        assert_eq!(game.board[Vector2::new(0, 8)].state, CellState::Flagged);
+    }
+
+    #[test]
+    fn fits_the_mines() {
+        for (size, mines, fitting) in [((9, 9), 200, 72), ((0, 0), 10, 0), ((4, 4), -3, 0)] {
+            let settings = GameSettings {
+                size: Vector2::new(size.0, size.1),
+                mines,
+            };
+            let mut game = Game::new(settings, 1);
+            game.reveal(&Vector2::new(0, 0));
+
+            assert_eq!(game.settings().mines, fitting);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_layout() {
+        let mut first = Game::new(SETTINGS, 7);
+        let mut second = Game::new(SETTINGS, 7);
+        first.reveal(&Vector2::new(0, 0));
+        second.reveal(&Vector2::new(0, 0));
+
+        assert_eq!(first.board, second.board);
+
+        // the mines depend on the first reveal, opening it again plays the same mines
+        let start = first.first_reveal().unwrap();
+        let mut restarted = Game::new(SETTINGS, 7);
+        assert!(matches!(restarted.reveal(&start), RevealOutcome::Opened(_)));
+        assert_eq!(restarted.board, first.board);
+    }
+
+    #[test]
+    fn chord_and_win() {
+        // one mine in the corner of a 3x1 board
+        let mut game = Game::with_settings(
+            GameSettings {
+                size: Vector2::new(3, 1),
+                mines: 1,
+            },
+            0,
+        );
+        game.board[Vector2::new(0, 0)].value = CellValue::Bomb;
+        game.board.update_bombs_around();
+        game.mines_placed = true;
+
+        game.reveal(&Vector2::new(1, 0));
+        assert_eq!(game.chord(&Vector2::new(1, 0)), RevealOutcome::Ignored);
+
+        assert_eq!(game.flag(&Vector2::new(0, 0)), Some(CellState::Flagged));
+        assert_eq!(
+            game.chord(&Vector2::new(1, 0)),
+            RevealOutcome::Opened(vec![Vector2::new(2, 0)])
+        );
+        assert_eq!(game.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn keeps_flags_when_lost() {
+        let board = board_text::parse("minesweeper-board 1\nsize 4 1\nmines 2\n*Ff.\n").unwrap();
+        let mut game = Game::from_board(board);
+
+        assert_eq!(
+            game.reveal(&Vector2::new(0, 0)),
+            RevealOutcome::Exploded(Vector2::new(0, 0))
+        );
+        assert_eq!(game.exploded(), Some(Vector2::new(0, 0)));
+        assert!(game.board_text().unwrap().ends_with("XFf.\n"));
+    }
+
+    #[test]
+    fn plays_a_board_from_text() {
+        let board = board_text::parse("minesweeper-board 1\nsize 3 1\nmines 1\n*1.\n").unwrap();
+        let mut game = Game::from_board(board);
+
+        assert_eq!(game.settings().mines, 1);
+        assert!(game.mines_placed());
+        assert_eq!(
+            game.reveal(&Vector2::new(2, 0)),
+            RevealOutcome::Opened(vec![Vector2::new(2, 0)])
+        );
+        assert_eq!(game.status(), GameStatus::Won);
+        assert!(game.board_text().unwrap().ends_with("*10\n"));
+    }
+}
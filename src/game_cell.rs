@@ -2,25 +2,42 @@ use ratatui::{prelude::*, widgets::*};
 use vctr2::vector2::Vector2;
 
 use crate::{
-    game_types::{Cell, CellState, CellValue, GameSettings},
-    game_utils::get_bombs_around,
+    board::Board,
+    game_types::{CellState, CellValue},
+    theme::Theme,
 };
 
-pub struct GameCell {
+pub struct GameCell<'a> {
     position: Vector2<u16>,
-    cells: Vec<Vec<Cell>>,
-    game_settings: GameSettings,
+    board: &'a Board,
+    theme: Theme,
+    game_over: bool,
+    exploded: bool,
 }
 
-impl GameCell {
-    pub fn new(position: Vector2<u16>, cells: Vec<Vec<Cell>>, game_settings: GameSettings) -> Self {
+impl<'a> GameCell<'a> {
+    pub fn new(position: Vector2<u16>, board: &'a Board, theme: Theme) -> Self {
         Self {
             position,
-            cells,
-            game_settings,
+            board,
+            theme,
+            game_over: false,
+            exploded: false,
         }
     }
 
+    /// Once the game is over the board tells mines apart, so wrong flags are crossed out.
+    pub fn game_over(mut self, game_over: bool) -> Self {
+        self.game_over = game_over;
+        self
+    }
+
+    /// The mine that was opened and ended the game.
+    pub fn exploded(mut self, exploded: bool) -> Self {
+        self.exploded = exploded;
+        self
+    }
+
     fn get_merged_borders(
         &self,
         base_border_set: &symbols::border::Set,
@@ -32,7 +49,7 @@ impl GameCell {
             top_right: symbols::line::TOP_RIGHT,
             bottom_left: symbols::line::BOTTOM_LEFT,
             bottom_right: symbols::line::BOTTOM_RIGHT,
-            ..base_border_set.clone()
+            ..*base_border_set
         };
 
         if x >= 1 && y == 0 {
@@ -47,11 +64,11 @@ impl GameCell {
             border_set.top_left = symbols::line::CROSS;
         }
 
-        if x >= 1 && y == self.game_settings.size.y - 1 {
+        if x >= 1 && y == self.board.size().y - 1 {
             border_set.bottom_left = symbols::line::HORIZONTAL_UP;
         }
 
-        if x == self.game_settings.size.x - 1 && y >= 1 {
+        if x == self.board.size().x - 1 && y >= 1 {
             border_set.top_right = symbols::line::VERTICAL_LEFT;
         }
 
@@ -59,59 +76,70 @@ impl GameCell {
     }
 }
 
-impl Widget for GameCell {
+impl Widget for GameCell<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if (self.position.y as usize) >= self.cells.len() {
+        let Some(&cell) = self.board.get(&self.position) else {
             return;
-        }
-        if (self.position.x as usize) >= self.cells[self.position.y as usize].len() {
-            return;
-        }
-        let cell = self.cells[self.position.y as usize][self.position.x as usize];
+        };
         let cell_symbol;
-        let cell_color;
+        let mut cell_style = Style::default();
 
         match cell.state {
             CellState::Closed => {
-                cell_symbol = '#';
-                cell_color = Color::Black;
+                cell_symbol = self.theme.glyphs.closed;
+                cell_style = cell_style.fg(self.theme.closed).bg(self.theme.closed_bg);
             }
             CellState::Opened => match cell.value {
                 CellValue::Empty => {
-                    let count =
-                        get_bombs_around(&self.game_settings.size, &self.position, &self.cells);
+                    let count = cell.bombs_around;
 
                     cell_symbol = if count == 0 {
-                        ' '
+                        self.theme.glyphs.empty
                     } else {
                         count.to_string().chars().nth(0).unwrap()
                     };
-                    cell_color = Color::Yellow;
+                    cell_style = cell_style.bg(self.theme.opened_bg);
+                    if count != 0 {
+                        cell_style = cell_style
+                            .fg(self.theme.number(count))
+                            .add_modifier(self.theme.number_modifier);
+                    }
+                }
+                CellValue::Bomb if self.exploded => {
+                    cell_symbol = self.theme.glyphs.exploded;
+                    cell_style = cell_style.fg(self.theme.opened_bg).bg(self.theme.mine);
                 }
                 CellValue::Bomb => {
-                    cell_symbol = '∅';
-                    cell_color = Color::Red;
+                    cell_symbol = self.theme.glyphs.mine;
+                    cell_style = cell_style.fg(self.theme.mine);
                 }
             },
+            CellState::Flagged if self.game_over && cell.value == CellValue::Empty => {
+                cell_symbol = self.theme.glyphs.wrong_flag;
+                cell_style = cell_style
+                    .fg(self.theme.mine)
+                    .add_modifier(Modifier::CROSSED_OUT);
+            }
             CellState::Flagged => {
-                cell_symbol = '⚑';
-                cell_color = Color::Red;
+                cell_symbol = self.theme.glyphs.flag;
+                cell_style = cell_style.fg(self.theme.flag);
+            }
+            CellState::Questioned => {
+                cell_symbol = self.theme.glyphs.question;
+                cell_style = cell_style.fg(self.theme.flag).bg(self.theme.closed_bg);
             }
         };
 
         let border_set =
             self.get_merged_borders(&symbols::border::PLAIN, self.position.x, self.position.y);
 
-        let mut block = Block::bordered()
+        let block = Block::bordered()
             .border_set(border_set)
-            .border_style(Color::Gray);
-
-        if cell.state == CellState::Opened && cell.value == CellValue::Empty {
-            block = block.bg(cell_color);
-        }
+            .border_style(Style::default().fg(self.theme.border))
+            .bg(cell_style.bg.unwrap_or_default());
 
         Paragraph::new(format!(" {} ", cell_symbol))
-            .fg(cell_color)
+            .style(cell_style)
             .block(block)
             .render(area, buf);
     }
@@ -5,6 +5,8 @@ pub enum CellState {
     Closed,
     Opened,
     Flagged,
+    /// Marked with a question mark, otherwise behaves like a closed cell
+    Questioned,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,6 +19,8 @@ pub enum CellValue {
 pub struct Cell {
     pub state: CellState,
     pub value: CellValue,
+    /// Number of bombs in the neighbouring cells, filled in once the bombs are placed.
+    pub bombs_around: u8,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1,62 +1,97 @@
 use vctr2::vector2::Vector2;
 
-use crate::game_types::{Cell, CellValue};
+use crate::{
+    board::Board,
+    game_types::{CellState, CellValue},
+};
 
-pub(crate) fn get_neighbours(
+const NEIGHBOUR_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Neighbours inside the board, clockwise from the top left one, without allocating.
+pub fn neighbours(
     game_size: &Vector2<u16>,
     position: &Vector2<u16>,
-) -> Vec<Vector2<u16>> {
-    let position_i32 = Vector2::new(position.x as i32, position.y as i32);
-
-    let neighbours_i32: Vec<Vector2<i32>> = vec![
-        Vector2::new(position_i32.x - 1, position_i32.y - 1),
-        Vector2::new(position_i32.x, position_i32.y - 1),
-        Vector2::new(position_i32.x + 1, position_i32.y - 1),
-        Vector2::new(position_i32.x + 1, position_i32.y),
-        Vector2::new(position_i32.x + 1, position_i32.y + 1),
-        Vector2::new(position_i32.x, position_i32.y + 1),
-        Vector2::new(position_i32.x - 1, position_i32.y + 1),
-        Vector2::new(position_i32.x - 1, position_i32.y),
-    ];
-
-    let mut neighbours = Vec::new();
-
-    for neighbour_i32 in neighbours_i32 {
-        if neighbour_i32.x >= 0
-            && neighbour_i32.x <= (game_size.x as i32) - 1
-            && neighbour_i32.y >= 0
-            && neighbour_i32.y <= (game_size.y as i32) - 1
-        {
-            neighbours.push(Vector2::new(neighbour_i32.x as u16, neighbour_i32.y as u16));
+) -> impl Iterator<Item = Vector2<u16>> {
+    let size = Vector2::new(game_size.x as i32, game_size.y as i32);
+    let position = Vector2::new(position.x as i32, position.y as i32);
+
+    NEIGHBOUR_OFFSETS.iter().filter_map(move |(dx, dy)| {
+        let x = position.x + dx;
+        let y = position.y + dy;
+
+        if x >= 0 && x < size.x && y >= 0 && y < size.y {
+            Some(Vector2::new(x as u16, y as u16))
+        } else {
+            None
         }
-    }
+    })
+}
 
-    neighbours
+pub fn get_neighbours(game_size: &Vector2<u16>, position: &Vector2<u16>) -> Vec<Vector2<u16>> {
+    neighbours(game_size, position).collect()
 }
 
-pub(crate) fn get_bombs_around(
-    game_size: &Vector2<u16>,
-    position: &Vector2<u16>,
-    cells: &Vec<Vec<Cell>>,
-) -> u16 {
-    let neighbours_pos = get_neighbours(game_size, position);
-    let mut bombs = 0;
-
-    for neighbour_pos in neighbours_pos {
-        let cell = cells[neighbour_pos.y as usize][neighbour_pos.x as usize];
-        if cell.value == CellValue::Bomb {
-            bombs += 1
+/// Opens the cell and spreads the opening over every neighbour of a cell without bombs around.
+///
+/// Returns the newly opened cells, starting with `position`. The spreading uses an explicit
+/// stack, so even huge empty boards can't overflow the call stack.
+pub fn open_cell(board: &mut Board, position: &Vector2<u16>) -> Vec<Vector2<u16>> {
+    let cell = &mut board[*position];
+    if cell.state == CellState::Opened {
+        return Vec::new();
+    }
+
+    cell.state = CellState::Opened;
+    let mut opened = vec![*position];
+
+    if cell.value == CellValue::Bomb || cell.bombs_around != 0 {
+        return opened;
+    }
+
+    // cells without bombs around, whose neighbours still have to be opened
+    let mut spread = vec![*position];
+    let size = board.size();
+
+    while let Some(spread_pos) = spread.pop() {
+        for neighbour_pos in neighbours(&size, &spread_pos) {
+            let cell = &mut board[neighbour_pos];
+
+            if matches!(cell.state, CellState::Opened | CellState::Flagged)
+                || cell.value != CellValue::Empty
+            {
+                continue;
+            }
+
+            cell.state = CellState::Opened;
+            opened.push(neighbour_pos);
+
+            if cell.bombs_around == 0 {
+                spread.push(neighbour_pos);
+            }
         }
     }
 
-    bombs
+    opened
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::DIFFICULY_BEGINNER;
-
     use super::*;
+    use crate::game_types::GameSettings;
+
+    const DIFFICULY_BEGINNER: GameSettings = GameSettings {
+        size: Vector2::new(9, 9),
+        mines: 10,
+    };
 
     #[test]
     fn get_neightbours() {
@@ -79,4 +114,30 @@ mod tests {
             vec![Vector2::new(1, 0), Vector2::new(1, 1), Vector2::new(0, 1),]
         );
     }
+
+    #[test]
+    fn open_cell_spreads_over_questioned() {
+        let mut board = Board::new(Vector2::new(4, 1));
+        board[Vector2::new(1, 0)].state = CellState::Questioned;
+        board[Vector2::new(2, 0)].state = CellState::Flagged;
+        board[Vector2::new(3, 0)].value = CellValue::Bomb;
+        board.update_bombs_around();
+
+        assert_eq!(
+            open_cell(&mut board, &Vector2::new(0, 0)),
+            vec![Vector2::new(0, 0), Vector2::new(1, 0)]
+        );
+        assert_eq!(board[Vector2::new(1, 0)].state, CellState::Opened);
+        assert_eq!(board[Vector2::new(2, 0)].state, CellState::Flagged);
+    }
+
+    #[test]
+    fn open_cell_huge_empty_board() {
+        let mut board = Board::new(Vector2::new(1000, 1000));
+
+        let opened = open_cell(&mut board, &Vector2::new(500, 500));
+
+        assert_eq!(opened.len(), 1_000_000);
+        assert_eq!(opened[0], Vector2::new(500, 500));
+    }
 }
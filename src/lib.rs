@@ -0,0 +1,12 @@
+//! Board model, rules and solver of minesweeper, shared by the game, bots and the benchmarks.
+//!
+//! [`game::Game`] plays a whole game without a user interface. Build with
+//! `default-features = false` to leave out the terminal frontend and its dependencies.
+
+pub mod board;
+pub mod board_import;
+pub mod board_text;
+pub mod game;
+pub mod game_types;
+pub mod game_utils;
+pub mod solver;
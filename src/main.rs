@@ -1,366 +1,1538 @@
 use std::{
-    io::{stdout, Result},
-    str::FromStr,
+    fs,
+    io::{stdout, Error, ErrorKind as IoErrorKind, Result},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
+use board::Board;
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use cli::{Cli, Command};
+use config::Config;
 use constants::*;
+use crash::TerminalGuard;
+use demo::Demo;
+use game::{Game, GameStatus};
 use game_cell::GameCell;
 use game_option::GameOption;
 use game_types::{Cell, CellState, CellValue, GameSettings};
-use game_utils::{get_bombs_around, get_neighbours};
+use minesweeper::{board, board_import, board_text, game, game_types, game_utils, solver};
 use rand::Rng;
 use ratatui::{
     backend::CrosstermBackend,
-    crossterm::{
-        event::{self, Event, KeyCode},
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-        ExecutableCommand,
-    },
+    crossterm::event::{self, Event, KeyCode},
     prelude::*,
     widgets::*,
 };
+use replay::{Move, MoveKind, Replay};
+use solver::{Action, Technique};
+use stdio::Session;
+use stopwatch::Stopwatch;
+use theme::ThemeName;
+use trainer::{technique_name, Trainer, PUZZLE_SETTINGS};
+use tutorial::{Tutorial, LESSONS};
 use vctr2::vector2::Vector2;
 
+mod announce;
+mod bench;
+mod board_image;
+mod cast;
+mod cli;
+mod config;
 mod constants;
+mod crash;
+mod demo;
 mod game_cell;
 mod game_option;
-mod game_types;
-mod game_utils;
+mod replay;
+mod stdio;
+mod stopwatch;
+mod theme;
+mod trainer;
+mod tutorial;
 
 fn main() -> Result<()> {
-    stdout().execute(EnterAlternateScreen)?;
-    enable_raw_mode()?;
+    let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Bench(args)) => return bench::run(args),
+        Some(Command::Cast(args)) => return cast::run(&cli, args),
+        None => {}
+    }
+
+    let game_settings = cli.game_settings().unwrap_or_else(|error| {
+        Cli::command()
+            .error(ErrorKind::ValueValidation, error)
+            .exit()
+    });
+
+    if cli.stdio {
+        let game_settings = game_settings.unwrap_or(DIFFICULY_BEGINNER);
+        return Session::new(game_settings, cli.seed, cli.no_guess).run();
+    }
+
+    let config = Config::load()?;
+    let mut app = App::new(&cli, &config);
+
+    if let Some(path) = &cli.load {
+        app.load_game(Replay::load(path)?);
+    } else if let Some(path) = &cli.replay {
+        app.start_replay(Replay::load(path)?);
+    } else if let Some(path) = &cli.board {
+        app.start_board(load_board(path)?);
+    } else if let Some(game_settings) = game_settings {
+        app.choose_difficulty(game_settings);
+    }
+
+    crash::install_panic_hook();
+    let guard = TerminalGuard::enter()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
-    let mut app = App::new();
 
     loop {
         terminal.draw(|frame| app.render(frame))?;
 
-        if app.process_event().unwrap_or(true) {
+        if app.process_event()? {
             break;
         }
     }
 
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
+    drop(guard);
+    if let Some(text) = &app.board_dump {
+        print!("{}", text);
+    }
+
     Ok(())
 }
 
+/// Reads a board file in the format of [`board_text`], or the mine layout of an MBF, AVF or RMV
+/// file, told apart by the extension.
+fn load_board(path: &Path) -> Result<Board> {
+    let format = path
+        .extension()
+        .and_then(|extension| board_import::Format::from_extension(&extension.to_string_lossy()));
+    let board = match format {
+        Some(format) => board_import::parse(format, &fs::read(path)?),
+        None => board_text::parse(&fs::read_to_string(path)?),
+    };
+
+    board
+        .and_then(|board| cli::check_board_size(board.size()).map(|()| board))
+        .map_err(|error| {
+            Error::new(
+                IoErrorKind::InvalidData,
+                format!("{}: {}", path.display(), error),
+            )
+        })
+}
+
 #[allow(dead_code)]
 enum AppState {
     Menu,
     Playing,
+    Paused,
+    Replaying,
     Dead,
+    Won,
+    /// The solver plays on its own, see [`Demo`].
+    Demo,
+    Tutorial,
+    /// Puzzles with a single deduction, see [`Trainer`].
+    Trainer,
+}
+
+struct ReplayPlayback {
+    moves: Vec<Move>,
+    next: usize,
+    started_at: Instant,
 }
 
 #[allow(dead_code)]
 struct App {
-    cells: Vec<Vec<Cell>>,
+    game: Game,
+    /// The board as the player sees it, updated after every move.
+    board: Board,
     cursor: Vector2<u16>,
-    mines_created: bool,
+    /// Top left cell shown, when the board is bigger than the terminal.
+    scroll: Vector2<u16>,
 
     state: AppState,
     game_settings: GameSettings,
+    /// Difficulty the menu comes back to, the last one chosen there or on the command line.
+    difficulty: GameSettings,
+
+    /// Seed given on the command line, used instead of a random one: the nth new game gets the
+    /// seed + n, so every new board is different but a run can be played again.
+    fixed_seed: Option<u64>,
+    /// New games dealt so far, restarts of the same mines don't count.
+    games_started: u64,
+    no_guess: bool,
+    moves: Vec<Move>,
+    stopwatch: Stopwatch,
+    playback: Option<ReplayPlayback>,
+    demo: Option<Demo>,
+    tutorial: Option<Tutorial>,
+    trainer: Option<Trainer>,
+    status_message: Option<String>,
+    /// The board the game started on, when it was loaded instead of generated from the seed.
+    layout: Option<Board>,
+    /// Board text printed to stdout once the terminal is restored.
+    board_dump: Option<String>,
+    theme_name: ThemeName,
+    /// Flagging a flagged cell marks it with a question mark instead of closing it.
+    question_marks: bool,
+    /// Linear output for screen readers instead of the grid.
+    screen_reader: bool,
+    announcement: String,
 }
 
 impl App {
-    pub fn new() -> Self {
-        Self {
-            cells: Self::generate_empty_cells(&DIFFICULY_BEGINNER),
+    pub fn new(cli: &Cli, config: &Config) -> Self {
+        let mut app = Self {
+            game: Game::new(DIFFICULY_BEGINNER, 0),
+            board: Board::new(DIFFICULY_BEGINNER.size),
             cursor: Vector2::new(0, 0),
-            mines_created: false,
+            scroll: Vector2::new(0, 0),
 
             state: AppState::Menu,
             game_settings: DIFFICULY_BEGINNER,
-        }
+            difficulty: DIFFICULY_BEGINNER,
+
+            fixed_seed: cli.seed,
+            games_started: 0,
+            no_guess: cli.no_guess,
+            moves: Vec::new(),
+            stopwatch: Stopwatch::default(),
+            playback: None,
+            demo: None,
+            tutorial: None,
+            trainer: None,
+            status_message: None,
+            layout: None,
+            board_dump: None,
+            theme_name: cli
+                .theme
+                .or(config.theme)
+                .unwrap_or_else(ThemeName::from_env),
+            question_marks: config.question_marks,
+            screen_reader: cli.screen_reader || config.screen_reader,
+            announcement: String::new(),
+        };
+
+        app.announce_menu();
+        app
     }
 
     pub fn render(&mut self, frame: &mut Frame) {
-        self.render_minesweeper(frame);
+        if self.screen_reader {
+            self.render_linear(frame);
+            return;
+        }
+
+        let required = self.required_size();
+        let frame_size = frame.size();
+        if frame_size.width < required.x || frame_size.height < required.y {
+            self.render_too_small(frame, required);
+            return;
+        }
+
+        let visible = self.visible_cells(frame_size);
+        self.scroll_to_cursor(visible);
+
+        if let AppState::Paused = self.state {
+            self.render_pause(frame, visible);
+        } else {
+            self.render_minesweeper(frame, visible);
+        }
 
         match self.state {
             AppState::Menu => {
-                let area = Rect::new(3, 1, (self.game_settings.size.x * (CELL_WIDTH - 1)) - 5, 10);
-                frame.render_widget(Clear::default(), area);
+                let area = Rect::new(MENU_X, MENU_Y, MENU_WIDTH, MENU_HEIGHT);
+                frame.render_widget(Clear, area);
                 frame.render_widget(Block::bordered(), area);
 
                 frame.render_widget(
                     Paragraph::new(Line::from(vec![
-                        "Minesweeper".bold().into(),
-                        ".rs"
-                            .bold()
-                            .fg(Color::from_str("#E43716").unwrap_or(Color::LightRed))
-                            .into(),
+                        "Minesweeper".bold(),
+                        ".rs".bold().fg(self.theme_name.theme().title),
                     ])),
-                    Rect::new(4, 2, area.width, 1),
+                    Rect::new(area.x + 1, area.y + 1, area.width - 2, 1),
                 );
 
-                frame.render_widget(
-                    GameOption::default()
-                        .title("Beginner".to_string())
-                        .selected(self.cursor.y == 0),
-                    Rect::new(5, 4, area.width, 1),
-                );
+                for (index, title) in MENU_OPTIONS.iter().enumerate() {
+                    frame.render_widget(
+                        GameOption::default()
+                            .title(title.to_string())
+                            .selected(self.cursor.y as usize == index),
+                        Rect::new(area.x + 2, area.y + 3 + index as u16, area.width - 3, 1),
+                    );
+                }
+            }
+            AppState::Playing | AppState::Replaying => {
+                self.render_cursor(frame, visible, &self.cursor);
+            }
+            AppState::Demo => {
+                if let Some(demo) = &self.demo {
+                    self.render_highlighted(frame, visible, &demo.sources);
+                    if let Some(target) = demo.target {
+                        self.render_cursor(frame, visible, &target);
+                    }
+                }
+            }
+            AppState::Tutorial => {
+                if let Some(tutorial) = &self.tutorial {
+                    self.render_highlighted(frame, visible, &tutorial.highlighted);
+                    self.render_cursor(frame, visible, &self.cursor);
+                    self.render_lesson(frame, tutorial, visible);
+                }
+            }
+            AppState::Trainer => {
+                if let Some(trainer) = &self.trainer {
+                    if let Some(puzzle) = trainer.puzzle.as_ref().filter(|_| trainer.solved) {
+                        if puzzle.deduction.technique != Technique::Global {
+                            let mut sources = vec![puzzle.deduction.source];
+                            sources.extend(puzzle.deduction.subset);
+                            self.render_highlighted(frame, visible, &sources);
+                        }
+                    }
+                    self.render_cursor(frame, visible, &self.cursor);
+                    self.render_puzzle(frame, trainer, visible);
+                }
+            }
+            AppState::Dead | AppState::Won => {
+                self.render_cursor(frame, visible, &self.cursor);
+                self.render_game_over(frame, visible);
+            }
+            _ => {}
+        }
 
-                frame.render_widget(
-                    GameOption::default()
-                        .title("Intermediate".to_string())
-                        .selected(self.cursor.y == 1),
-                    Rect::new(5, 5, area.width, 1),
-                );
+        let mut y = visible.y * (CELL_HEIGHT - 1) + 1;
+        if self.has_side_panel() {
+            y = y.max(SIDE_PANEL_HEIGHT);
+        }
 
-                frame.render_widget(
-                    GameOption::default()
-                        .title("Expert".to_string())
-                        .selected(self.cursor.y == 2),
-                    Rect::new(5, 6, area.width, 1),
-                );
-            }
-            AppState::Playing => {
-                // Cursor
+        if !matches!(self.state, AppState::Menu) {
+            frame.render_widget(
+                Paragraph::new(self.status_line()),
+                Rect::new(0, y, frame_size.width, 1),
+            );
+        }
 
-                let area_x = self.cursor.x * (CELL_WIDTH - 1);
-                let area_y = self.cursor.y * (CELL_HEIGHT - 1);
+        if let Some(message) = &self.status_message {
+            frame.render_widget(
+                Paragraph::new(message.as_str()),
+                Rect::new(0, y + 1, frame_size.width, 1),
+            );
+        }
+    }
 
-                let area = Rect::new(area_x, area_y, CELL_WIDTH, CELL_HEIGHT);
+    /// Smallest terminal that shows the menu, or at least a few cells of the board and the
+    /// status lines below them.
+    fn required_size(&self) -> Vector2<u16> {
+        let cells = Vector2::new(
+            self.game_settings.size.x.min(MIN_VISIBLE_CELLS),
+            self.game_settings.size.y.min(MIN_VISIBLE_CELLS),
+        );
+        let mut size = Vector2::new(
+            cells.x * (CELL_WIDTH - 1) + 1,
+            cells.y * (CELL_HEIGHT - 1) + 1 + STATUS_HEIGHT,
+        );
 
-                frame.render_widget(
-                    Block::bordered()
-                        .border_set(symbols::border::DOUBLE)
-                        .border_style(Color::Green),
-                    area,
-                )
+        match self.state {
+            AppState::Menu => {
+                size.x = size.x.max(MENU_X + MENU_WIDTH);
+                size.y = size.y.max(MENU_Y + MENU_HEIGHT);
+            }
+            _ if self.has_side_panel() => {
+                size.x += SIDE_PANEL_WIDTH + 1;
+                size.y = size.y.max(SIDE_PANEL_HEIGHT + STATUS_HEIGHT);
             }
             _ => {}
         }
+
+        size
     }
 
-    fn render_minesweeper(&mut self, frame: &mut Frame) {
-        for y in 0..self.game_settings.size.y {
-            for x in 0..self.game_settings.size.x {
-                let position = Vector2::new(x, y);
+    /// Whether a panel is shown next to the board, see [`App::render_side_panel`].
+    fn has_side_panel(&self) -> bool {
+        matches!(
+            self.state,
+            AppState::Tutorial | AppState::Trainer | AppState::Dead | AppState::Won
+        )
+    }
+
+    /// How many cells fit into the terminal, cells share their borders.
+    fn visible_cells(&self, area: Rect) -> Vector2<u16> {
+        let mut width = area.width;
+        if self.has_side_panel() {
+            width = width.saturating_sub(SIDE_PANEL_WIDTH + 1);
+        }
+
+        Vector2::new(
+            (width.saturating_sub(1) / (CELL_WIDTH - 1)).min(self.game_settings.size.x),
+            (area.height.saturating_sub(1 + STATUS_HEIGHT) / (CELL_HEIGHT - 1))
+                .min(self.game_settings.size.y),
+        )
+    }
+
+    /// Moves the shown part of the board just enough to keep the cursor in it.
+    fn scroll_to_cursor(&mut self, visible: Vector2<u16>) {
+        let size = self.game_settings.size;
+        self.scroll.x = self.scroll.x.min(size.x - visible.x);
+        self.scroll.y = self.scroll.y.min(size.y - visible.y);
+
+        if let AppState::Menu = self.state {
+            return;
+        }
 
-                let area_x = position.x * (CELL_WIDTH - 1);
-                let area_y = position.y * (CELL_HEIGHT - 1);
-                let area = Rect::new(area_x, area_y, CELL_WIDTH, CELL_HEIGHT);
+        if self.cursor.x < self.scroll.x {
+            self.scroll.x = self.cursor.x;
+        } else if self.cursor.x >= self.scroll.x + visible.x {
+            self.scroll.x = self.cursor.x + 1 - visible.x;
+        }
+
+        if self.cursor.y < self.scroll.y {
+            self.scroll.y = self.cursor.y;
+        } else if self.cursor.y >= self.scroll.y + visible.y {
+            self.scroll.y = self.cursor.y + 1 - visible.y;
+        }
+    }
+
+    fn render_cursor(&self, frame: &mut Frame, visible: Vector2<u16>, position: &Vector2<u16>) {
+        if self.is_shown(position, visible) {
+            frame.render_widget(
+                Block::bordered()
+                    .border_set(symbols::border::DOUBLE)
+                    .border_style(self.theme_name.theme().cursor),
+                self.cell_area(position),
+            );
+        }
+    }
+
+    /// Frames the numbers a step of the demo or a lesson is about.
+    fn render_highlighted(&self, frame: &mut Frame, visible: Vector2<u16>, cells: &[Vector2<u16>]) {
+        for position in cells.iter().filter(|p| self.is_shown(p, visible)) {
+            frame.render_widget(
+                Block::bordered()
+                    .border_set(symbols::border::THICK)
+                    .border_style(self.theme_name.theme().title),
+                self.cell_area(position),
+            );
+        }
+    }
+
+    /// Side panel next to the board with the text of the lesson.
+    fn render_lesson(&self, frame: &mut Frame, tutorial: &Tutorial, visible: Vector2<u16>) {
+        let lesson = tutorial.current();
+
+        let mut text = vec![Line::from(lesson.text), Line::from("")];
+        text.push(Line::from(if tutorial.is_done() {
+            "Well done! Press Enter to go on."
+        } else {
+            "Enter opens, F flags, Esc leaves."
+        }));
+
+        let title = format!(
+            " Lesson {}/{}: {} ",
+            tutorial.lesson + 1,
+            LESSONS.len(),
+            lesson.title
+        );
+        self.render_side_panel(frame, visible, title, text);
+    }
+
+    /// Side panel next to the board with the task, the technique and the score.
+    fn render_puzzle(&self, frame: &mut Frame, trainer: &Trainer, visible: Vector2<u16>) {
+        let mut text = vec![Line::from(match &trainer.puzzle {
+            Some(puzzle) => format!(
+                "Exactly one cell follows from the numbers and the {} mines left. \
+                 Open it with Enter if it is safe, flag it with F if it is a mine.",
+                puzzle.mines_left()
+            ),
+            None => "No puzzle found for this technique.".to_string(),
+        })];
+        text.push(Line::from(""));
+        text.push(Line::from(format!(
+            "Technique: {}, Tab changes it.",
+            technique_name(trainer.technique)
+        )));
+        text.push(Line::from(format!("{}.", trainer.score())));
+        text.push(Line::from(""));
+        text.push(Line::from(if trainer.solved {
+            "Enter for the next puzzle, Esc leaves."
+        } else {
+            "Esc leaves."
+        }));
+
+        self.render_side_panel(frame, visible, " Puzzle trainer ".to_string(), text);
+    }
+
+    /// Side panel next to the finished board with how the game went and what to do next.
+    fn render_game_over(&self, frame: &mut Frame, visible: Vector2<u16>) {
+        let mut text: Vec<Line> = self.game_stats().into_iter().map(Line::from).collect();
+        text.push(Line::from(""));
+        for (key, choice) in GAME_OVER_CHOICES {
+            text.push(Line::from(vec![key.bold(), format!(" {}", choice).into()]));
+        }
+        text.push(Line::from(""));
+        text.push(Line::from("I saves a picture, B prints the board on quit."));
+
+        let title = match self.state {
+            AppState::Won => " You won ",
+            _ => " Game over ",
+        };
+        self.render_side_panel(frame, visible, title.to_string(), text);
+    }
+
+    /// How the finished game went, a line each.
+    fn game_stats(&self) -> Vec<String> {
+        let elapsed = self.stopwatch.elapsed().as_secs();
+        let difficulty = match self.game_settings {
+            DIFFICULY_BEGINNER => "Beginner",
+            DIFFICULY_INTERMEDIATE => "Intermediate",
+            DIFFICULY_EXPERT => "Expert",
+            _ => "Custom",
+        };
+        let count = |f: fn(&Cell) -> bool| self.board.cells().filter(|cell| f(cell)).count();
+
+        vec![
+            format!(
+                "Time {}:{:02}, {} moves",
+                elapsed / 60,
+                elapsed % 60,
+                self.moves.len()
+            ),
+            format!(
+                "{}: {}x{}, {} mines",
+                difficulty,
+                self.game_settings.size.x,
+                self.game_settings.size.y,
+                self.game_settings.mines
+            ),
+            format!(
+                "{} of {} safe cells opened",
+                count(|cell| cell.value == CellValue::Empty && cell.state == CellState::Opened),
+                count(|cell| cell.value == CellValue::Empty)
+            ),
+            format!(
+                "{} of {} flags right",
+                count(|cell| cell.value == CellValue::Bomb && cell.state == CellState::Flagged),
+                count(|cell| cell.state == CellState::Flagged)
+            ),
+        ]
+    }
+
+    fn render_side_panel(
+        &self,
+        frame: &mut Frame,
+        visible: Vector2<u16>,
+        title: String,
+        text: Vec<Line>,
+    ) {
+        let x = visible.x * (CELL_WIDTH - 1) + 2;
+        let area = Rect::new(x, 0, SIDE_PANEL_WIDTH, SIDE_PANEL_HEIGHT).intersection(frame.size());
+
+        frame.render_widget(
+            Paragraph::new(text).wrap(Wrap { trim: true }).block(
+                Block::bordered()
+                    .border_style(self.theme_name.theme().border)
+                    .title(title),
+            ),
+            area,
+        );
+    }
+
+    fn is_shown(&self, position: &Vector2<u16>, visible: Vector2<u16>) -> bool {
+        (self.scroll.x..self.scroll.x + visible.x).contains(&position.x)
+            && (self.scroll.y..self.scroll.y + visible.y).contains(&position.y)
+    }
+
+    /// Where a shown cell is drawn in the terminal.
+    fn cell_area(&self, position: &Vector2<u16>) -> Rect {
+        Rect::new(
+            (position.x - self.scroll.x) * (CELL_WIDTH - 1),
+            (position.y - self.scroll.y) * (CELL_HEIGHT - 1),
+            CELL_WIDTH,
+            CELL_HEIGHT,
+        )
+    }
+
+    fn render_too_small(&self, frame: &mut Frame, required: Vector2<u16>) {
+        let area = frame.size();
+        let text = vec![
+            Line::from("Terminal too small".bold()),
+            Line::from(format!("needs {}x{}", required.x, required.y)),
+            Line::from(format!("now {}x{}", area.width, area.height)),
+        ];
+
+        frame.render_widget(
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            Rect::new(
+                0,
+                area.height.saturating_sub(3) / 2,
+                area.width,
+                area.height.min(3),
+            ),
+        );
+    }
+
+    /// Covers the board, so that it can not be studied while the timer is stopped.
+    fn render_pause(&self, frame: &mut Frame, visible: Vector2<u16>) {
+        let board = Rect::new(
+            0,
+            0,
+            visible.x * (CELL_WIDTH - 1) + 1,
+            visible.y * (CELL_HEIGHT - 1) + 1,
+        );
+
+        frame.render_widget(Clear, board);
+        frame.render_widget(
+            Block::bordered().border_style(self.theme_name.theme().border),
+            board,
+        );
+
+        let text = "Paused – press P to resume";
+        let y = board.height / 2;
+        if y < board.height {
+            frame.render_widget(
+                Paragraph::new(text.bold()).alignment(Alignment::Center),
+                Rect::new(board.x, board.y + y, board.width, 1),
+            );
+        }
+    }
+
+    fn status_line(&self) -> String {
+        if let Some(trainer) = &self.trainer {
+            return format!("Puzzle trainer – {}", trainer.score());
+        }
+
+        if let Some(tutorial) = &self.tutorial {
+            return format!(
+                "Tutorial – lesson {} of {}",
+                tutorial.lesson + 1,
+                LESSONS.len()
+            );
+        }
+
+        if let Some(demo) = &self.demo {
+            return format!(
+                "Solver demo – a step every {} ms, +/- change the speed, Esc stops",
+                demo.delay.as_millis()
+            );
+        }
+
+        let elapsed = self.stopwatch.elapsed().as_secs();
+        let flagged = self
+            .board
+            .cells()
+            .filter(|cell| cell.state == CellState::Flagged)
+            .count() as i32;
+
+        format!(
+            "Time {}:{:02}   Mines {}/{}",
+            elapsed / 60,
+            elapsed % 60,
+            self.game_settings.mines - flagged,
+            self.game_settings.mines
+        )
+    }
+
+    /// Plain lines of text for screen readers, the terminal cursor is left after the announcement.
+    fn render_linear(&mut self, frame: &mut Frame) {
+        let area = frame.size();
+        let mut lines: Vec<Line> = vec![Line::from("Minesweeper.rs")];
+
+        match self.state {
+            AppState::Menu => {
+                for (index, title) in MENU_OPTIONS.iter().enumerate() {
+                    let symbol = if self.cursor.y as usize == index {
+                        ">"
+                    } else {
+                        " "
+                    };
+                    lines.push(Line::from(format!("{} {}", symbol, title)));
+                }
+            }
+            AppState::Dead | AppState::Won => {
+                lines.extend(self.game_stats().into_iter().map(Line::from));
+            }
+            _ => {
+                lines.push(Line::from(format!(
+                    "{}x{} board, {} mines",
+                    self.game_settings.size.x, self.game_settings.size.y, self.game_settings.mines
+                )));
+                lines.push(Line::from(self.status_line()));
+            }
+        }
+
+        let announcement_y = lines.len() as u16;
+        lines.push(Line::from(self.announcement.as_str()));
+
+        if let Some(message) = &self.status_message {
+            lines.push(Line::from(message.as_str()));
+        }
+        lines.push(Line::from(match self.state {
+            AppState::Menu => "Up and down choose, Enter starts, Esc quits",
+            AppState::Playing => {
+                "Arrows move, Enter opens, F flags, A restarts the board, N starts a new one, \
+                 R reads the row, C reads the cell, Esc gives up"
+            }
+            AppState::Paused => "Paused, P resumes",
+            AppState::Replaying => "Esc stops the replay",
+            AppState::Dead | AppState::Won => {
+                "A plays the same mines again, N starts a new game, V watches the replay, I saves a \
+                 picture, B prints the board on quit, C reads the cell, Esc goes back to the menu"
+            }
+            AppState::Demo => "Plus and minus change the speed, Esc stops the demo",
+            AppState::Tutorial => {
+                "Arrows move, Enter opens, F flags, C reads the cell, L reads the lesson, Esc leaves"
+            }
+            AppState::Trainer => {
+                "Arrows move, Enter opens, F flags, Tab changes the technique, C reads the cell, \
+                 L reads the puzzle, Esc leaves"
+            }
+        }));
+
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+
+        let cursor_x = (self.announcement.chars().count() as u16).min(area.width.saturating_sub(1));
+        if announcement_y < area.height {
+            frame.set_cursor(cursor_x, announcement_y);
+        }
+    }
+
+    /// Updates the line read out by screen readers with the cell under the cursor.
+    fn announce_cursor(&mut self) {
+        let mut announcement = announce::describe_cell(&self.board, &self.cursor);
+
+        if let AppState::Dead = self.state {
+            announcement = format!("Game over, {}", announcement);
+        }
+
+        self.announcement = announcement;
+    }
+
+    fn announce_menu(&mut self) {
+        self.announcement = format!(
+            "{}, {} of {}",
+            MENU_OPTIONS[self.cursor.y as usize],
+            self.cursor.y + 1,
+            MENU_OPTIONS.len()
+        );
+    }
+
+    fn render_minesweeper(&self, frame: &mut Frame, visible: Vector2<u16>) {
+        let theme = self.theme_name.theme();
+        // the tutorial and the trainer show their own boards
+        let game_over = self.game.status() != GameStatus::Playing
+            && !matches!(self.state, AppState::Tutorial | AppState::Trainer);
+        let exploded = self.game.exploded().filter(|_| game_over);
+
+        for y in self.scroll.y..self.scroll.y + visible.y {
+            for x in self.scroll.x..self.scroll.x + visible.x {
+                let position = Vector2::new(x, y);
 
                 frame.render_widget(
-                    GameCell::new(position, self.cells.to_vec(), self.game_settings.clone()),
-                    area,
+                    GameCell::new(position, &self.board, theme)
+                        .game_over(game_over)
+                        .exploded(exploded == Some(position)),
+                    self.cell_area(&position),
                 );
             }
         }
     }
 
     pub fn process_event(&mut self) -> Result<bool> {
-        if event::poll(std::time::Duration::from_millis(16))? {
-            let read_event = event::read()?;
+        if let Some(timeout) = self.next_tick() {
+            if !event::poll(timeout)? {
+                self.tick();
+                return Ok(false);
+            }
+        }
+
+        let read_event = event::read()?;
 
-            match self.state {
-                AppState::Menu => match read_event {
-                    Event::Key(key) => match key.code {
+        // the next draw lays everything out for the new size
+        if let Event::Resize(..) = read_event {
+            return Ok(false);
+        }
+
+        match self.state {
+            AppState::Menu => {
+                if let Event::Key(key) = read_event {
+                    match key.code {
                         KeyCode::Esc => {
                             return Ok(true);
                         }
-                        KeyCode::Up => {
-                            if self.cursor.y != 0 {
-                                self.cursor.y -= 1;
+                        KeyCode::Up if self.cursor.y != 0 => {
+                            self.cursor.y -= 1;
+                        }
+                        KeyCode::Down if (self.cursor.y as usize) < MENU_OPTIONS.len() - 1 => {
+                            self.cursor.y += 1;
+                        }
+                        KeyCode::Char('t') => self.next_theme(),
+                        KeyCode::Enter => {
+                            if let Some(&difficulty) = DIFFICULTIES.get(self.cursor.y as usize) {
+                                self.choose_difficulty(difficulty);
+                            } else if self.cursor.y == 3 {
+                                self.start_demo();
+                            } else if self.cursor.y == 4 {
+                                self.start_tutorial(0);
+                            } else if self.cursor.y == 5 {
+                                self.start_trainer();
                             }
                         }
-                        KeyCode::Down => {
-                            if self.cursor.y != 2 {
-                                self.cursor.y += 1;
+                        _ => {}
+                    }
+
+                    if let AppState::Menu = self.state {
+                        self.announce_menu();
+                    } else {
+                        self.announce_cursor();
+                    }
+                }
+            }
+            AppState::Playing => {
+                if let Event::FocusLost = read_event {
+                    self.pause();
+                }
+
+                if let Event::Key(key) = read_event {
+                    let mut announce_cursor = true;
+
+                    // k
+                    match key.code {
+                        KeyCode::Esc => {
+                            if self.game.mines_placed() {
+                                self.game.resign();
+                                self.update_board();
+                            } else {
+                                self.reset_game();
                             }
                         }
-                        KeyCode::Enter => {
-                            if self.cursor.y == 0 {
-                                self.game_settings = DIFFICULY_BEGINNER;
-                                self.cells = Self::generate_empty_cells(&DIFFICULY_BEGINNER);
-                            } else if self.cursor.y == 1 {
-                                self.game_settings = DIFFICULY_INTERMEDIATE;
-                                self.cells = Self::generate_empty_cells(&DIFFICULY_INTERMEDIATE);
-                            } else if self.cursor.y == 2 {
-                                self.game_settings = DIFFICULY_EXPERT;
-                                self.cells = Self::generate_empty_cells(&DIFFICULY_EXPERT);
+                        KeyCode::Enter => self.play_move(MoveKind::Open, self.cursor),
+                        KeyCode::Char('f') => self.play_move(MoveKind::Flag, self.cursor),
+                        KeyCode::Char('a') => self.restart(),
+                        KeyCode::Char('n') => self.start_game(self.game_settings),
+                        KeyCode::Char('s') => self.save_game(),
+                        KeyCode::Char('b') => self.dump_board(),
+                        KeyCode::Char('t') => self.next_theme(),
+                        KeyCode::Char('p') => self.pause(),
+                        // read out again below
+                        KeyCode::Char('c') => {}
+                        KeyCode::Char('r') => {
+                            self.announcement = announce::describe_row(&self.board, self.cursor.y);
+                            announce_cursor = false;
+                        }
+                        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                            self.move_cursor(key.code)
+                        }
+                        _ => {}
+                    }
+
+                    match self.state {
+                        AppState::Menu => self.announce_menu(),
+                        _ if announce_cursor => self.announce_cursor(),
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Paused => {
+                if let Event::Key(key) = read_event {
+                    if matches!(key.code, KeyCode::Char('p') | KeyCode::Enter) {
+                        self.resume();
+                    }
+                }
+            }
+            AppState::Replaying => {
+                if let Event::Key(key) = read_event {
+                    if key.code == KeyCode::Esc {
+                        self.reset_game();
+                    }
+                }
+            }
+            AppState::Demo => {
+                if let Event::Key(key) = read_event {
+                    match key.code {
+                        KeyCode::Esc => {
+                            self.reset_game();
+                            self.announce_menu();
+                        }
+                        KeyCode::Char('+' | '=') => {
+                            if let Some(demo) = &mut self.demo {
+                                demo.faster();
+                            }
+                        }
+                        KeyCode::Char('-') => {
+                            if let Some(demo) = &mut self.demo {
+                                demo.slower();
                             }
+                        }
+                        KeyCode::Char('t') => self.next_theme(),
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Tutorial => {
+                if let Event::Key(key) = read_event {
+                    let mut announce_cursor = true;
 
-                            self.cursor = Vector2::new(0, 0);
-                            self.state = AppState::Playing;
+                    match key.code {
+                        KeyCode::Esc => {
+                            self.reset_game();
+                        }
+                        KeyCode::Enter if self.tutorial.as_ref().is_some_and(Tutorial::is_done) => {
+                            self.next_lesson();
+                            announce_cursor = false;
+                        }
+                        KeyCode::Enter => self.tutorial_move(Action::Open),
+                        KeyCode::Char('f') => self.tutorial_move(Action::Flag),
+                        KeyCode::Char('t') => self.next_theme(),
+                        KeyCode::Char('l') => {
+                            self.announce_lesson();
+                            announce_cursor = false;
+                        }
+                        KeyCode::Char('c') => {}
+                        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                            self.move_cursor(key.code)
                         }
                         _ => {}
-                    },
-                    _ => {}
-                },
-                AppState::Playing => {
-                    match read_event {
-                        Event::Key(key) => {
-                            // k
-                            match key.code {
-                                KeyCode::Esc => {
-                                    if self.mines_created {
-                                        self.open_all_mines();
-                                        self.state = AppState::Dead;
-                                    } else {
-                                        self.state = AppState::Menu;
-                                    }
-                                }
-                                KeyCode::Enter => {
-                                    if !self.mines_created {
-                                        self.generate_cells();
-                                        self.mines_created = true;
-                                    }
-                                    self.open_cell(&self.cursor.clone())
-                                }
-                                KeyCode::Char('f') => self.flag_cell(&self.cursor.clone()),
-                                // Cursor movement
-                                KeyCode::Left => {
-                                    if self.cursor.x > 0 {
-                                        self.cursor.x -= 1;
-                                    }
-                                }
-                                KeyCode::Right => {
-                                    if self.cursor.x < self.game_settings.size.x - 1 {
-                                        self.cursor.x += 1;
-                                    }
-                                }
-                                KeyCode::Up => {
-                                    if self.cursor.y > 0 {
-                                        self.cursor.y -= 1;
-                                    }
-                                }
-                                KeyCode::Down => {
-                                    if self.cursor.y < self.game_settings.size.y - 1 {
-                                        self.cursor.y += 1;
-                                    }
-                                }
-                                _ => {}
+                    }
+
+                    match self.state {
+                        AppState::Menu => self.announce_menu(),
+                        _ if announce_cursor => self.announce_cursor(),
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Trainer => {
+                if let Event::Key(key) = read_event {
+                    let mut announce_cursor = true;
+
+                    match key.code {
+                        KeyCode::Esc => {
+                            self.reset_game();
+                        }
+                        KeyCode::Enter if self.trainer.as_ref().is_some_and(|t| t.solved) => {
+                            if let Some(trainer) = &mut self.trainer {
+                                trainer.next_puzzle();
+                            }
+                            self.show_puzzle();
+                            announce_cursor = false;
+                        }
+                        KeyCode::Enter => announce_cursor = !self.trainer_answer(Action::Open),
+                        KeyCode::Char('f') => announce_cursor = !self.trainer_answer(Action::Flag),
+                        KeyCode::Tab => {
+                            if let Some(trainer) = &mut self.trainer {
+                                trainer.next_technique();
                             }
+                            self.show_puzzle();
+                            announce_cursor = false;
+                        }
+                        KeyCode::Char('t') => self.next_theme(),
+                        KeyCode::Char('l') => {
+                            self.announce_puzzle();
+                            announce_cursor = false;
+                        }
+                        KeyCode::Char('c') => {}
+                        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                            self.move_cursor(key.code)
                         }
+                        _ => {}
+                    }
 
+                    match self.state {
+                        AppState::Menu => self.announce_menu(),
+                        _ if announce_cursor => self.announce_cursor(),
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Dead | AppState::Won => {
+                if let Event::Key(key) = read_event {
+                    match key.code {
+                        KeyCode::Char('a') => self.restart(),
+                        KeyCode::Char('n') => self.start_game(self.game_settings),
+                        KeyCode::Char('v') => self.start_replay(self.replay()),
+                        KeyCode::Char('b') => self.dump_board(),
+                        KeyCode::Char('i') => self.save_image(),
+                        KeyCode::Char('t') => self.next_theme(),
+                        KeyCode::Char('c') => self.announce_cursor(),
+                        KeyCode::Esc => {
+                            self.reset_game();
+                            self.announce_menu();
+                        }
+                        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                            self.move_cursor(key.code);
+                            self.announce_cursor();
+                        }
                         _ => {}
                     }
                 }
-                AppState::Dead => self.reset_game(),
             }
         }
 
         Ok(false)
     }
 
-    fn generate_empty_cells(game_settings: &GameSettings) -> Vec<Vec<Cell>> {
-        let mut cells = Vec::new();
+    /// Starts a game of the difficulty and comes back to it in the menu.
+    fn choose_difficulty(&mut self, game_settings: GameSettings) {
+        self.difficulty = game_settings;
+        self.start_game(game_settings);
+    }
+
+    fn start_game(&mut self, game_settings: GameSettings) {
+        let seed = match self.fixed_seed {
+            Some(seed) => seed.wrapping_add(self.games_started),
+            None => rand::thread_rng().gen(),
+        };
+        self.games_started += 1;
+        self.start_seeded_game(game_settings, seed);
+    }
+
+    fn start_seeded_game(&mut self, game_settings: GameSettings, seed: u64) {
+        self.game_settings = game_settings;
+        self.game = Game::new(game_settings, seed)
+            .with_no_guess(self.no_guess)
+            .with_question_marks(self.question_marks);
+        self.board = self.game.visible_board();
+        self.layout = None;
+        self.moves.clear();
+        self.stopwatch = Stopwatch::default();
+        self.status_message = None;
+
+        self.cursor = Vector2::new(0, 0);
+        self.state = AppState::Playing;
+        self.announce_cursor();
+        crash::set_game(self.replay());
+    }
+
+    /// Starts a game on a fixed board, e.g. one loaded from a file.
+    fn start_board(&mut self, layout: Board) {
+        let game = Game::from_board(layout.clone()).with_question_marks(self.question_marks);
 
-        for _y in 0..game_settings.size.x {
-            let mut row = Vec::new();
+        self.start_seeded_game(game.settings(), game.seed());
+        self.game = game;
+        self.layout = Some(layout);
+        self.update_board();
+        crash::set_game(self.replay());
+    }
 
-            for _x in 0..game_settings.size.y {
-                row.push(Cell {
-                    state: CellState::Closed,
-                    value: CellValue::Empty,
-                })
+    /// Plays the exact same mines again from the start, or the loaded position of a board file.
+    /// The mines depend on the first reveal, so it is opened again as the first move.
+    fn restart(&mut self) {
+        match self.layout.clone() {
+            Some(layout) => self.start_board(layout),
+            None => {
+                let first_reveal = self.game.first_reveal();
+                self.start_seeded_game(self.game_settings, self.game.seed());
+                if let Some(position) = first_reveal {
+                    self.cursor = position;
+                    self.play_move(MoveKind::Open, position);
+                    self.announce_cursor();
+                }
             }
+        }
+    }
+
+    fn load_game(&mut self, replay: Replay) {
+        self.no_guess = replay.no_guess;
+        self.question_marks = replay.question_marks;
+        match &replay.layout {
+            Some(layout) => self.start_board(layout.clone()),
+            None => self.start_seeded_game(replay.game_settings, replay.seed),
+        }
 
-            cells.push(row)
+        for m in &replay.moves {
+            self.apply_move(m);
         }
 
-        cells
+        let elapsed = Duration::from_millis(replay.moves.last().map_or(0, |m| m.time_ms));
+        self.stopwatch = Stopwatch::with_elapsed(elapsed);
+        if let AppState::Playing = self.state {
+            self.stopwatch.start();
+        }
+        self.moves = replay.moves;
+        crash::set_game(self.replay());
     }
 
-    fn generate_cells(&mut self) {
-        let mut rng = rand::thread_rng();
+    fn start_replay(&mut self, replay: Replay) {
+        self.no_guess = replay.no_guess;
+        self.question_marks = replay.question_marks;
+        match &replay.layout {
+            Some(layout) => self.start_board(layout.clone()),
+            None => self.start_seeded_game(replay.game_settings, replay.seed),
+        }
 
-        let mut count = self.game_settings.mines;
-        let excluded_positions = get_neighbours(&self.game_settings.size, &self.cursor);
+        // kept to watch the replay again from the game-over panel
+        self.moves = replay.moves.clone();
+        crash::set_game(self.replay());
+        self.state = AppState::Replaying;
+        self.status_message = Some("Replay – Esc to stop".to_string());
+        self.playback = Some(ReplayPlayback {
+            moves: replay.moves,
+            next: 0,
+            started_at: Instant::now(),
+        });
+        self.advance_replay();
+    }
 
-        while count > 0 {
-            let x = rng.gen_range(0..self.game_settings.size.x);
-            let y = rng.gen_range(0..self.game_settings.size.y);
+    /// How long to wait for an event before the screen needs an update on its own, `None` to
+    /// sleep until the next event.
+    fn next_tick(&self) -> Option<Duration> {
+        match self.state {
+            AppState::Replaying => {
+                let playback = self.playback.as_ref()?;
+                let m = playback.moves.get(playback.next)?;
 
-            if excluded_positions.contains(&Vector2::new(x, y)) {
-                continue;
+                Some(Duration::from_millis(m.time_ms).saturating_sub(playback.started_at.elapsed()))
+            }
+            // the timer is redrawn when its seconds change, screen readers hear the time on actions
+            AppState::Playing if self.stopwatch.is_running() && !self.screen_reader => {
+                let millis = self.stopwatch.elapsed().subsec_millis();
+                Some(Duration::from_millis(1000 - millis as u64))
             }
+            AppState::Demo => self.demo.as_ref().map(Demo::next_step_in),
+            _ => None,
+        }
+    }
 
-            if self.cells[y as usize][x as usize].value != CellValue::Bomb {
-                self.cells[y as usize][x as usize].value = CellValue::Bomb;
-                count -= 1;
+    fn tick(&mut self) {
+        match self.state {
+            AppState::Replaying => self.advance_replay(),
+            AppState::Demo => self.demo_step(),
+            _ => {}
+        }
+    }
+
+    fn move_cursor(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Left if self.cursor.x > 0 => {
+                self.cursor.x -= 1;
+            }
+            KeyCode::Right if self.cursor.x < self.game_settings.size.x - 1 => {
+                self.cursor.x += 1;
+            }
+            KeyCode::Up if self.cursor.y > 0 => {
+                self.cursor.y -= 1;
             }
+            KeyCode::Down if self.cursor.y < self.game_settings.size.y - 1 => {
+                self.cursor.y += 1;
+            }
+            _ => {}
         }
     }
 
-    //     fn get_cell_symbol(&self, x: u16, y: u16) -> char {
-    //         // bomb neightbours: 12345678
-    //         // got bomb: Ⓑ//🅱
-    //         // flagged: ⚑
+    fn start_tutorial(&mut self, lesson: usize) {
+        let tutorial = Tutorial::new(lesson);
 
-    //     }
-    //
-    fn open_cell(&mut self, position: &Vector2<u16>) {
-        {
-            let cell = &mut self.cells[position.y as usize][position.x as usize];
-            cell.state = CellState::Opened;
+        self.board = tutorial.board.clone();
+        self.game_settings = GameSettings {
+            size: self.board.size(),
+            mines: self
+                .board
+                .cells()
+                .filter(|cell| cell.value == CellValue::Bomb)
+                .count() as i32,
         };
+        self.tutorial = Some(tutorial);
+        self.cursor = Vector2::new(0, 0);
+        self.scroll = Vector2::new(0, 0);
+        self.state = AppState::Tutorial;
+        self.status_message = None;
+        self.announce_lesson();
+    }
+
+    fn next_lesson(&mut self) {
+        let next = self
+            .tutorial
+            .as_ref()
+            .map_or(0, |tutorial| tutorial.lesson + 1);
 
-        let cell = &self.cells[position.y as usize][position.x as usize];
+        if next < LESSONS.len() {
+            self.start_tutorial(next);
+        } else {
+            self.reset_game();
+            self.status_message = Some("Tutorial finished, have fun!".to_string());
+        }
+    }
 
-        if cell.value == CellValue::Bomb {
-            self.open_all_mines();
+    /// Makes the move in the lesson, or tells the player why it is not the right one.
+    fn tutorial_move(&mut self, action: Action) {
+        let Some(tutorial) = &mut self.tutorial else {
             return;
+        };
+
+        self.status_message = Some(match tutorial.try_move(&self.cursor, action) {
+            Ok(()) if tutorial.is_done() => "Well done! Press Enter to go on.".to_string(),
+            Ok(()) => "Right!".to_string(),
+            Err(message) => message,
+        });
+        self.board = tutorial.board.clone();
+    }
+
+    fn announce_lesson(&mut self) {
+        if let Some(tutorial) = &self.tutorial {
+            let lesson = tutorial.current();
+            self.announcement = format!(
+                "Lesson {}, {}. {}",
+                tutorial.lesson + 1,
+                lesson.title,
+                lesson.text
+            );
         }
+    }
 
-        let no_bombs_around =
-            get_bombs_around(&self.game_settings.size, &position, &self.cells) == 0;
+    fn start_trainer(&mut self) {
+        let seed = self.fixed_seed.unwrap_or_else(|| rand::thread_rng().gen());
 
-        if cell.value == CellValue::Empty && no_bombs_around {
-            // spread opening
-            for neighbour_pos in get_neighbours(&self.game_settings.size, position) {
-                let cell = &mut self.cells[neighbour_pos.y as usize][neighbour_pos.x as usize];
+        self.trainer = Some(Trainer::new(seed));
+        self.game_settings = PUZZLE_SETTINGS;
+        self.cursor = Vector2::new(0, 0);
+        self.scroll = Vector2::new(0, 0);
+        self.state = AppState::Trainer;
+        self.show_puzzle();
+    }
 
-                if cell.state != CellState::Closed {
-                    continue;
-                }
+    /// Shows the current puzzle, or its solution once it is solved.
+    fn show_puzzle(&mut self) {
+        let Some(trainer) = &self.trainer else {
+            return;
+        };
+
+        self.board = match &trainer.puzzle {
+            Some(puzzle) if trainer.solved => puzzle.solution.clone(),
+            Some(puzzle) => puzzle.board.clone(),
+            None => Board::new(PUZZLE_SETTINGS.size),
+        };
+        self.status_message = None;
+        self.announce_puzzle();
+    }
 
-                match cell.value {
-                    CellValue::Empty => {
-                        cell.state = CellState::Opened;
+    /// Checks the answer and tells the player how it went, returns whether it was right.
+    fn trainer_answer(&mut self, action: Action) -> bool {
+        let Some(trainer) = &mut self.trainer else {
+            return false;
+        };
 
-                        if get_bombs_around(&self.game_settings.size, &neighbour_pos, &self.cells)
-                            == 0
-                        {
-                            self.open_cell(&neighbour_pos);
-                        }
-                    }
-                    _ => {}
-                }
+        let (right, message) = match trainer.answer(&self.cursor, action) {
+            Ok(explanation) => (true, explanation),
+            Err(hint) => (false, hint),
+        };
+        if right {
+            self.show_puzzle();
+            self.announcement = message.clone();
+        }
+        self.status_message = Some(message);
+
+        right
+    }
+
+    fn announce_puzzle(&mut self) {
+        if let Some(trainer) = &self.trainer {
+            self.announcement = match &trainer.puzzle {
+                Some(puzzle) => format!(
+                    "Puzzle, {} technique, {} mines left. Find the one cell that follows from \
+                     the numbers.",
+                    technique_name(trainer.technique),
+                    puzzle.mines_left()
+                ),
+                None => "No puzzle found for this technique, Tab changes it.".to_string(),
+            };
+        }
+    }
+
+    fn start_demo(&mut self) {
+        self.start_game(DIFFICULY_INTERMEDIATE);
+        self.state = AppState::Demo;
+        self.demo = Some(Demo::new(self.game.seed()));
+        self.status_message = Some("The solver starts in a moment".to_string());
+        self.announcement = "Solver demo".to_string();
+    }
+
+    /// Lets the solver make its next move, or starts a new board when the last one is over.
+    fn demo_step(&mut self) {
+        let Some(mut demo) = self.demo.take() else {
+            return;
+        };
+
+        if demo.finished_at.is_some() {
+            self.start_demo();
+            if let Some(new_demo) = &mut self.demo {
+                new_demo.delay = demo.delay;
+            }
+            return;
+        }
+
+        let text = demo.step(&mut self.game);
+        self.board = self.game.visible_board();
+        if let Some(target) = demo.target {
+            self.cursor = target;
+        }
+
+        let text = match self.game.status() {
+            GameStatus::Playing => text,
+            GameStatus::Won => format!("{} Solved!", text),
+            GameStatus::Lost => format!("{} That was a mine.", text),
+        };
+        if self.game.status() != GameStatus::Playing {
+            demo.finished_at = Some(Instant::now());
+        }
+
+        self.announcement = text.clone();
+        self.status_message = Some(text);
+        self.demo = Some(demo);
+    }
+
+    fn advance_replay(&mut self) {
+        let Some(mut playback) = self.playback.take() else {
+            return;
+        };
+        let elapsed = playback.started_at.elapsed().as_millis() as u64;
+
+        while let Some(m) = playback.moves.get(playback.next) {
+            if m.time_ms > elapsed {
+                break;
             }
+
+            self.cursor = m.position;
+            self.stopwatch = Stopwatch::with_elapsed(Duration::from_millis(m.time_ms));
+            self.apply_move(m);
+            self.announce_cursor();
+            playback.next += 1;
+        }
+
+        if playback.next == playback.moves.len() {
+            self.status_message = Some(match self.state {
+                AppState::Replaying => "Replay finished – Esc to go back".to_string(),
+                // a replay of a finished game ends on the game-over panel
+                _ => "Replay finished".to_string(),
+            });
         }
+        self.playback = Some(playback);
     }
 
-    fn flag_cell(&mut self, position: &Vector2<u16>) {
-        let cell = &mut self.cells[position.y as usize][position.x as usize];
-        if cell.state == CellState::Closed {
-            cell.state = CellState::Flagged;
-        } else if cell.state == CellState::Flagged {
-            cell.state = CellState::Closed;
+    fn save_game(&mut self) {
+        let path = PathBuf::from(format!("minesweeper-{}.save", self.game.seed()));
+
+        self.status_message = Some(match self.replay().save(&path) {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(error) => format!("Could not save: {}", error),
+        });
+    }
+
+    /// The current game, as it is saved.
+    fn replay(&self) -> Replay {
+        Replay {
+            game_settings: self.game_settings,
+            seed: self.game.seed(),
+            no_guess: self.no_guess,
+            question_marks: self.question_marks,
+            layout: self.layout.clone(),
+            moves: self.moves.clone(),
         }
     }
 
-    fn open_all_mines(&mut self) {
-        self.state = AppState::Dead;
-        for y in 0..self.cells.len() {
-            for x in 0..self.cells[y].len() {
-                if self.cells[y][x].value == CellValue::Bomb {
-                    self.cells[y][x].state = CellState::Opened;
+    /// Writes a picture of the finished board, as SVG and, with the `png` feature, as PNG.
+    fn save_image(&mut self) {
+        let board = self.game.visible_board();
+        let exploded = self.game.exploded();
+        let name = format!("minesweeper-{}", self.game.seed());
+
+        let files = [
+            (
+                format!("{}.svg", name),
+                board_image::svg(&board, exploded).into_bytes(),
+            ),
+            #[cfg(feature = "png")]
+            (format!("{}.png", name), board_image::png(&board, exploded)),
+        ];
+
+        self.status_message = Some(
+            match files
+                .iter()
+                .try_for_each(|(path, bytes)| fs::write(path, bytes))
+            {
+                Ok(()) => format!(
+                    "Saved the picture to {}",
+                    files
+                        .iter()
+                        .map(|(path, _)| path.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                ),
+                Err(error) => format!("Could not save the picture: {}", error),
+            },
+        );
+    }
+
+    /// Keeps the board to print it once the game quits, the terminal shows the game until then.
+    /// Only a finished game is printed, the text holds every mine.
+    fn dump_board(&mut self) {
+        self.status_message = Some(match self.game.board_text() {
+            Some(text) => {
+                self.board_dump = Some(text);
+                "The board is printed when you quit".to_string()
+            }
+            None => "The board can be printed once the game is over".to_string(),
+        });
+    }
+
+    fn next_theme(&mut self) {
+        self.theme_name = self.theme_name.next();
+        self.status_message = Some(format!("Theme: {}", self.theme_name.title()));
+    }
+
+    /// Records the move of the player and applies it.
+    fn play_move(&mut self, kind: MoveKind, position: Vector2<u16>) {
+        self.stopwatch.start();
+        let m = Move {
+            kind,
+            position,
+            time_ms: self.stopwatch.elapsed().as_millis() as u64,
+        };
+
+        self.moves.push(m);
+        crash::add_move(m);
+        self.apply_move(&m);
+    }
+
+    fn apply_move(&mut self, m: &Move) {
+        match m.kind {
+            MoveKind::Open => {
+                let first = !self.game.mines_placed();
+                self.game.reveal(&m.position);
+
+                if first && self.game.needs_guessing() {
+                    self.status_message =
+                        Some("Could not find a board without guessing".to_string());
                 }
             }
+            MoveKind::Flag => {
+                self.game.flag(&m.position);
+            }
+        }
+
+        self.update_board();
+    }
+
+    /// Shows the board after a change of the game and ends it when it is over.
+    fn update_board(&mut self) {
+        self.board = self.game.visible_board();
+
+        match self.game.status() {
+            GameStatus::Playing => {}
+            GameStatus::Won => {
+                self.stopwatch.stop();
+                self.state = AppState::Won;
+                let elapsed = self.stopwatch.elapsed().as_secs();
+                self.status_message =
+                    Some(format!("You won in {}:{:02}!", elapsed / 60, elapsed % 60));
+            }
+            GameStatus::Lost => {
+                self.stopwatch.stop();
+                self.state = AppState::Dead;
+            }
+        }
+    }
+
+    //     fn get_cell_symbol(&self, x: u16, y: u16) -> char {
+    //         // bomb neightbours: 12345678
+    //         // got bomb: Ⓑ//🅱
+    //         // flagged: ⚑
+
+    //     }
+    //
+    fn pause(&mut self) {
+        self.stopwatch.stop();
+        self.state = AppState::Paused;
+        self.announcement = "Paused".to_string();
+    }
+
+    fn resume(&mut self) {
+        if !self.moves.is_empty() {
+            self.stopwatch.start();
         }
+        self.state = AppState::Playing;
+        self.announce_cursor();
     }
 
     fn reset_game(&mut self) {
-        self.cursor = Vector2::new(0, 0);
+        let menu_index = DIFFICULTIES.iter().position(|d| *d == self.difficulty);
+        self.cursor = Vector2::new(0, menu_index.unwrap_or(0) as u16);
         self.state = AppState::Menu;
-        self.game_settings = DIFFICULY_BEGINNER;
-        self.cells = Self::generate_empty_cells(&DIFFICULY_BEGINNER);
-        self.mines_created = false;
+        self.game_settings = self.difficulty;
+        self.game = Game::new(self.difficulty, 0);
+        self.board = self.game.visible_board();
+        self.playback = None;
+        self.demo = None;
+        self.tutorial = None;
+        self.trainer = None;
+        self.status_message = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(args: &[&str]) -> App {
+        let cli = Cli::parse_from([&["minesweeper"], args].concat());
+        App::new(&cli, &Config::default())
+    }
+
+    #[test]
+    fn restart_opens_the_first_reveal() {
+        let start = Vector2::new(4, 4);
+
+        for seed in 0..200 {
+            let mut app = app(&[]);
+            app.start_seeded_game(DIFFICULY_BEGINNER, seed);
+            app.play_move(MoveKind::Open, start);
+            let board = app.board.clone();
+
+            app.restart();
+
+            assert!(!matches!(app.state, AppState::Dead));
+            assert_eq!(app.cursor, start);
+            assert_eq!(app.board, board);
+            assert_eq!(app.moves.len(), 1);
+            assert_eq!(app.moves[0].kind, MoveKind::Open);
+            assert_eq!(app.moves[0].position, start);
+        }
+    }
+
+    #[test]
+    fn dumps_finished_boards_only() {
+        let mut app = app(&[]);
+        app.start_seeded_game(DIFFICULY_BEGINNER, 42);
+        app.play_move(MoveKind::Open, Vector2::new(4, 4));
+        app.play_move(MoveKind::Flag, Vector2::new(0, 0));
+
+        app.dump_board();
+        assert_eq!(app.board_dump, None);
+
+        app.game.resign();
+        app.update_board();
+        app.dump_board();
+
+        let text = app.board_dump.unwrap();
+        assert_eq!(board_text::write(&board_text::parse(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn new_games_count_up_from_the_seed() {
+        let mut app = app(&["--seed", "42"]);
+
+        app.start_game(DIFFICULY_BEGINNER);
+        assert_eq!(app.game.seed(), 42);
+        app.play_move(MoveKind::Open, Vector2::new(4, 4));
+        app.restart();
+        assert_eq!(app.game.seed(), 42);
+
+        app.start_game(DIFFICULY_BEGINNER);
+        assert_eq!(app.game.seed(), 43);
     }
 }
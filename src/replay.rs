@@ -0,0 +1,260 @@
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use vctr2::vector2::Vector2;
+
+use crate::{board::Board, board_text, cli, game_types::GameSettings};
+
+// --- Save/replay file --- //
+
+// minesweeper-replay 1
+// size 9 9
+// mines 10
+// seed 1234
+// no-guess false
+// question-marks true
+// open 4 4 0
+// flag 2 3 1520
+//
+// Every move stores the time in milliseconds since the first move. Saves and replays share the
+// format: a save is restored by applying all of its moves at once.
+//
+// Games on a board loaded from a file have one `layout ..*F.` line per row before the moves,
+// in the format of `board_text`, instead of placing mines from the seed.
+
+const HEADER: &str = "minesweeper-replay 1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    Open,
+    Flag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub kind: MoveKind,
+    pub position: Vector2<u16>,
+    pub time_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub game_settings: GameSettings,
+    pub seed: u64,
+    pub no_guess: bool,
+    /// Whether flagging a flagged cell put a question mark on it.
+    pub question_marks: bool,
+    /// The board the game started on, when it was loaded instead of generated from the seed.
+    pub layout: Option<Board>,
+    pub moves: Vec<Move>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        if lines.next() != Some(HEADER) {
+            return Err(invalid_data("not a minesweeper replay"));
+        }
+
+        let mut size = None;
+        let mut mines = None;
+        let mut seed = None;
+        let mut no_guess = false;
+        let mut question_marks = false;
+        let mut layout = Vec::new();
+        let mut moves = Vec::new();
+
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let key = words.next().unwrap_or_default();
+            let values: Vec<&str> = words.collect();
+
+            match (key, values.as_slice()) {
+                ("size", [x, y]) => size = Some(Vector2::new(parse(x)?, parse(y)?)),
+                ("mines", [count]) => mines = Some(parse(count)?),
+                ("seed", [value]) => seed = Some(parse(value)?),
+                ("no-guess", [value]) => no_guess = parse(value)?,
+                ("question-marks", [value]) => question_marks = parse(value)?,
+                ("layout", [row]) => layout.push(*row),
+                ("open" | "flag", [x, y, time_ms]) => moves.push(Move {
+                    kind: if key == "open" {
+                        MoveKind::Open
+                    } else {
+                        MoveKind::Flag
+                    },
+                    position: Vector2::new(parse(x)?, parse(y)?),
+                    time_ms: parse(time_ms)?,
+                }),
+                _ => return Err(invalid_data(&format!("unexpected line `{}`", line))),
+            }
+        }
+
+        let game_settings = GameSettings {
+            size: size.ok_or_else(|| invalid_data("missing size"))?,
+            mines: mines.ok_or_else(|| invalid_data("missing mines"))?,
+        };
+
+        // a loaded board may hold more mines than a generated one fits
+        if layout.is_empty() {
+            cli::check_game_settings(&game_settings)
+        } else {
+            cli::check_board_size(game_settings.size)
+        }
+        .map_err(|error| invalid_data(&error))?;
+
+        let outside = |position: &Vector2<u16>| {
+            position.x >= game_settings.size.x || position.y >= game_settings.size.y
+        };
+        if moves.iter().any(|m| outside(&m.position)) {
+            return Err(invalid_data("move outside of the board"));
+        }
+
+        let layout = if layout.is_empty() {
+            None
+        } else {
+            // checked before parsing, the size above is known to fit
+            if layout.len() != game_settings.size.y as usize
+                || layout
+                    .iter()
+                    .any(|row| row.chars().count() != game_settings.size.x as usize)
+            {
+                return Err(invalid_data(
+                    "the layout doesn't have the size of the board",
+                ));
+            }
+            Some(board_text::parse_rows(&layout).map_err(|error| invalid_data(&error))?)
+        };
+
+        Ok(Self {
+            game_settings,
+            seed: seed.ok_or_else(|| invalid_data("missing seed"))?,
+            no_guess,
+            question_marks,
+            layout,
+            moves,
+        })
+    }
+}
+
+impl std::fmt::Display for Replay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(
+            f,
+            "size {} {}",
+            self.game_settings.size.x, self.game_settings.size.y
+        )?;
+        writeln!(f, "mines {}", self.game_settings.mines)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "no-guess {}", self.no_guess)?;
+        writeln!(f, "question-marks {}", self.question_marks)?;
+
+        if let Some(layout) = &self.layout {
+            for row in board_text::rows(layout) {
+                writeln!(f, "layout {}", row)?;
+            }
+        }
+
+        for m in &self.moves {
+            let kind = match m.kind {
+                MoveKind::Open => "open",
+                MoveKind::Flag => "flag",
+            };
+            writeln!(
+                f,
+                "{} {} {} {}",
+                kind, m.position.x, m.position.y, m.time_ms
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid_data(&format!("invalid value `{}`", value)))
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let replay = Replay {
+            game_settings: GameSettings {
+                size: Vector2::new(30, 16),
+                mines: 99,
+            },
+            seed: 42,
+            no_guess: true,
+            question_marks: true,
+            layout: None,
+            moves: vec![
+                Move {
+                    kind: MoveKind::Open,
+                    position: Vector2::new(3, 4),
+                    time_ms: 0,
+                },
+                Move {
+                    kind: MoveKind::Flag,
+                    position: Vector2::new(29, 15),
+                    time_ms: 1250,
+                },
+            ],
+        };
+
+        assert_eq!(Replay::parse(&replay.to_string()).unwrap(), replay);
+
+        let replay = Replay {
+            game_settings: GameSettings {
+                size: Vector2::new(4, 4),
+                mines: 1,
+            },
+            layout: Some(board_text::parse_rows(&["*1..", "11..", "....", "...."]).unwrap()),
+            moves: Vec::new(),
+            ..replay
+        };
+        assert_eq!(Replay::parse(&replay.to_string()).unwrap(), replay);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(Replay::parse("hello").is_err());
+        assert!(Replay::parse("minesweeper-replay 1\nsize 9 9\nmines 10").is_err());
+        assert!(
+            Replay::parse("minesweeper-replay 1\nsize 9 9\nmines 10\nseed 1\nopen 9 0 0").is_err()
+        );
+
+        for settings in [
+            "size 9 9\nmines 200",
+            "size 0 0\nmines 10",
+            "size 9 9\nmines 75",
+            &format!("size 4 4\nmines 1\nlayout {}", "*".repeat(70_000)),
+        ] {
+            let text = format!("minesweeper-replay 1\n{}\nseed 1", settings);
+            assert_eq!(
+                Replay::parse(&text).unwrap_err().kind(),
+                ErrorKind::InvalidData
+            );
+        }
+    }
+}
@@ -0,0 +1,492 @@
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game_types::{CellState, CellValue},
+    game_utils::open_cell,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Open,
+    Flag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Technique {
+    /// A single number whose remaining mines are either zero or equal to its closed neighbours.
+    Basic,
+    /// The closed neighbours of one number are a subset of another's.
+    Subset,
+    /// The number of mines left on the whole board settles the remaining cells.
+    Global,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deduction {
+    pub position: Vector2<u16>,
+    pub action: Action,
+    pub technique: Technique,
+    /// The opened cell whose number the deduction was made from, the deduced cell itself for
+    /// global deductions.
+    pub source: Vector2<u16>,
+    /// For subset deductions, the number whose closed neighbours lie within the source's.
+    pub subset: Option<Vector2<u16>>,
+}
+
+/// What an opened number cell tells about its still unknown neighbours.
+struct Constraint {
+    source: Vector2<u16>,
+    unknown: Vec<Vector2<u16>>,
+    mines_left: i32,
+}
+
+/// Collects every deduction that follows from the visible state of the board.
+///
+/// Only opened numbers and flags are looked at, the value of closed cells is never read, so
+/// flags are trusted to be correct.
+pub fn find_deductions(board: &Board) -> Vec<Deduction> {
+    let constraints = collect_constraints(board);
+    let mut deductions: Vec<Deduction> = Vec::new();
+
+    let push = |deductions: &mut Vec<Deduction>, deduction: Deduction| {
+        if !deductions.iter().any(|d| d.position == deduction.position) {
+            deductions.push(deduction);
+        }
+    };
+
+    for constraint in &constraints {
+        let action = if constraint.mines_left == 0 {
+            Action::Open
+        } else if constraint.mines_left == constraint.unknown.len() as i32 {
+            Action::Flag
+        } else {
+            continue;
+        };
+
+        for position in &constraint.unknown {
+            push(
+                &mut deductions,
+                Deduction {
+                    position: *position,
+                    action,
+                    technique: Technique::Basic,
+                    source: constraint.source,
+                    subset: None,
+                },
+            );
+        }
+    }
+
+    if !deductions.is_empty() {
+        return deductions;
+    }
+
+    for small in &constraints {
+        for big in &constraints {
+            if small.unknown.len() >= big.unknown.len()
+                || !small.unknown.iter().all(|p| big.unknown.contains(p))
+            {
+                continue;
+            }
+
+            let rest: Vec<Vector2<u16>> = big
+                .unknown
+                .iter()
+                .filter(|p| !small.unknown.contains(p))
+                .copied()
+                .collect();
+            let mines_in_rest = big.mines_left - small.mines_left;
+
+            let action = if mines_in_rest == 0 {
+                Action::Open
+            } else if mines_in_rest == rest.len() as i32 {
+                Action::Flag
+            } else {
+                continue;
+            };
+
+            for position in rest {
+                push(
+                    &mut deductions,
+                    Deduction {
+                        position,
+                        action,
+                        technique: Technique::Subset,
+                        source: big.source,
+                        subset: Some(small.source),
+                    },
+                );
+            }
+        }
+    }
+
+    deductions
+}
+
+/// Like [`find_deductions`], but when the numbers alone decide nothing, the number of `mines`
+/// on the board is taken into account as well.
+pub fn find_deductions_with_mine_count(board: &Board, mines: i32) -> Vec<Deduction> {
+    let deductions = find_deductions(board);
+    if !deductions.is_empty() {
+        return deductions;
+    }
+
+    let unknown: Vec<Vector2<u16>> = board
+        .positions()
+        .filter(|p| matches!(board[*p].state, CellState::Closed | CellState::Questioned))
+        .collect();
+    let flagged = board
+        .cells()
+        .filter(|cell| cell.state == CellState::Flagged)
+        .count() as i32;
+    let mines_left = mines - flagged;
+
+    let global = |position: Vector2<u16>, action| Deduction {
+        position,
+        action,
+        technique: Technique::Global,
+        source: position,
+        subset: None,
+    };
+
+    if mines_left == unknown.len() as i32 {
+        return unknown
+            .into_iter()
+            .map(|p| global(p, Action::Flag))
+            .collect();
+    }
+
+    // numbers whose closed neighbours don't overlap need their mines_left each, when they add up
+    // to all mines left, every cell outside of them is safe
+    let mut packed: Vec<Vector2<u16>> = Vec::new();
+    let mut packed_mines = 0;
+    for constraint in collect_constraints(board) {
+        if constraint.unknown.iter().all(|p| !packed.contains(p)) {
+            packed.extend(&constraint.unknown);
+            packed_mines += constraint.mines_left;
+        }
+    }
+
+    if packed_mines != mines_left {
+        return Vec::new();
+    }
+
+    unknown
+        .into_iter()
+        .filter(|p| !packed.contains(p))
+        .map(|p| global(p, Action::Open))
+        .collect()
+}
+
+/// Every closed cell that holds a mine in all or in none of the ways to place the `mines` left
+/// around the numbers, found by trying all of them.
+///
+/// Unlike the deductions, nothing is missed, but the time grows exponentially with the closed
+/// cells next to numbers, so it is meant for small boards.
+pub fn forced_cells(board: &Board, mines: i32) -> Vec<(Vector2<u16>, Action)> {
+    let constraints = collect_constraints(board);
+    let unknown = board
+        .positions()
+        .filter(|p| matches!(board[*p].state, CellState::Closed | CellState::Questioned))
+        .count();
+    let flagged = board
+        .cells()
+        .filter(|cell| cell.state == CellState::Flagged)
+        .count() as i32;
+
+    // in the order the numbers list them, so that numbers are complete early and cut the search
+    let mut frontier: Vec<Vector2<u16>> = Vec::new();
+    for constraint in &constraints {
+        for position in &constraint.unknown {
+            if !frontier.contains(position) {
+                frontier.push(*position);
+            }
+        }
+    }
+
+    let mut search = Search {
+        constraints: constraints
+            .iter()
+            .map(|constraint| (constraint.mines_left, constraint.unknown.len() as i32))
+            .collect(),
+        touching: frontier
+            .iter()
+            .map(|position| {
+                (0..constraints.len())
+                    .filter(|&c| constraints[c].unknown.contains(position))
+                    .collect()
+            })
+            .collect(),
+        mines: vec![false; frontier.len()],
+        mines_left: mines - flagged,
+        others: (unknown - frontier.len()) as i32,
+        seen: vec![(false, false); frontier.len()],
+        others_seen: (false, false),
+    };
+    search.visit(0, 0);
+
+    let forced = |(mine, safe)| match (mine, safe) {
+        (true, false) => Some(Action::Flag),
+        (false, true) => Some(Action::Open),
+        _ => None,
+    };
+
+    let mut cells: Vec<(Vector2<u16>, Action)> = frontier
+        .iter()
+        .zip(&search.seen)
+        .filter_map(|(position, seen)| forced(*seen).map(|action| (*position, action)))
+        .collect();
+
+    if let Some(action) = forced(search.others_seen) {
+        cells.extend(
+            board
+                .positions()
+                .filter(|p| matches!(board[*p].state, CellState::Closed | CellState::Questioned))
+                .filter(|p| !frontier.contains(p))
+                .map(|p| (p, action)),
+        );
+    }
+
+    cells
+}
+
+/// Tries every placement of mines on the cells next to numbers, see [`forced_cells`].
+struct Search {
+    /// Mines still missing and cells not decided yet, for every number.
+    constraints: Vec<(i32, i32)>,
+    /// The numbers next to each cell.
+    touching: Vec<Vec<usize>>,
+    mines: Vec<bool>,
+    mines_left: i32,
+    /// Closed cells away from the numbers, they take the mines the numbers leave.
+    others: i32,
+    /// Whether each cell was seen with a mine and without one.
+    seen: Vec<(bool, bool)>,
+    others_seen: (bool, bool),
+}
+
+impl Search {
+    fn visit(&mut self, index: usize, placed: i32) {
+        if placed > self.mines_left {
+            return;
+        }
+
+        if index == self.mines.len() {
+            let rest = self.mines_left - placed;
+            if rest > self.others {
+                return;
+            }
+
+            for (seen, &mine) in self.seen.iter_mut().zip(&self.mines) {
+                if mine {
+                    seen.0 = true;
+                } else {
+                    seen.1 = true;
+                }
+            }
+            if self.others > 0 {
+                self.others_seen.0 |= rest > 0;
+                self.others_seen.1 |= rest < self.others;
+            }
+            return;
+        }
+
+        for mine in [false, true] {
+            let fits = self.touching[index].iter().all(|&c| {
+                let (missing, open) = self.constraints[c];
+                // the other undecided cells of the number can still take the mines it misses
+                (0..open).contains(&(missing - mine as i32))
+            });
+            if !fits {
+                continue;
+            }
+
+            for &c in &self.touching[index] {
+                self.constraints[c].0 -= mine as i32;
+                self.constraints[c].1 -= 1;
+            }
+            self.mines[index] = mine;
+
+            self.visit(index + 1, placed + mine as i32);
+
+            for &c in &self.touching[index] {
+                self.constraints[c].0 += mine as i32;
+                self.constraints[c].1 += 1;
+            }
+        }
+    }
+}
+
+/// Checks whether the board can be cleared from `start` without ever having to guess.
+pub fn is_solvable(board: &Board, start: &Vector2<u16>) -> bool {
+    let mut board = board.clone();
+
+    if board[*start].value == CellValue::Bomb {
+        return false;
+    }
+    open_cell(&mut board, start);
+
+    loop {
+        let deductions = find_deductions(&board);
+        if deductions.is_empty() {
+            break;
+        }
+
+        for deduction in deductions {
+            match deduction.action {
+                Action::Open => {
+                    open_cell(&mut board, &deduction.position);
+                }
+                Action::Flag => board[deduction.position].state = CellState::Flagged,
+            }
+        }
+    }
+
+    let solved = board
+        .cells()
+        .all(|cell| cell.value == CellValue::Bomb || cell.state == CellState::Opened);
+    solved
+}
+
+fn collect_constraints(board: &Board) -> Vec<Constraint> {
+    let mut constraints = Vec::new();
+
+    for position in board.positions() {
+        let cell = board[position];
+
+        if cell.state != CellState::Opened || cell.value != CellValue::Empty {
+            continue;
+        }
+
+        let mut unknown = Vec::new();
+        let mut flagged = 0;
+
+        for neighbour in board.neighbours(&position) {
+            match board[neighbour].state {
+                CellState::Closed | CellState::Questioned => unknown.push(neighbour),
+                CellState::Flagged => flagged += 1,
+                CellState::Opened => {}
+            }
+        }
+
+        if unknown.is_empty() {
+            continue;
+        }
+
+        constraints.push(Constraint {
+            source: position,
+            unknown,
+            mines_left: cell.bombs_around as i32 - flagged,
+        });
+    }
+
+    constraints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_board(rows: &[&str]) -> Board {
+        let mut board = Board::new(Vector2::new(rows[0].len() as u16, rows.len() as u16));
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let cell = &mut board[Vector2::new(x as u16, y as u16)];
+
+                if c == 'o' {
+                    cell.state = CellState::Opened;
+                }
+                if c == '*' {
+                    cell.value = CellValue::Bomb;
+                }
+            }
+        }
+        board.update_bombs_around();
+
+        board
+    }
+
+    #[test]
+    fn basic_flag() {
+        let board = parse_board(&["o*", "oo"]);
+
+        assert_eq!(
+            find_deductions(&board),
+            vec![Deduction {
+                position: Vector2::new(1, 0),
+                action: Action::Flag,
+                technique: Technique::Basic,
+                source: Vector2::new(0, 0),
+                subset: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn subset_open() {
+        // 1-1 pattern against a wall: the third cell above is safe
+        let board = parse_board(&["*..", "oo."]);
+
+        let deductions = find_deductions(&board);
+        assert!(deductions.contains(&Deduction {
+            position: Vector2::new(2, 0),
+            action: Action::Open,
+            technique: Technique::Subset,
+            source: Vector2::new(1, 1),
+            subset: Some(Vector2::new(0, 1)),
+        }));
+    }
+
+    #[test]
+    fn global_open() {
+        // the 1s share their mine, the only one on the board, so the far column is safe
+        let board = parse_board(&["o*.", "o.."]);
+
+        assert_eq!(find_deductions(&board), vec![]);
+        assert_eq!(
+            find_deductions_with_mine_count(&board, 1)
+                .iter()
+                .map(|d| (d.position, d.action, d.technique))
+                .collect::<Vec<_>>(),
+            vec![
+                (Vector2::new(2, 0), Action::Open, Technique::Global),
+                (Vector2::new(2, 1), Action::Open, Technique::Global),
+            ]
+        );
+    }
+
+    #[test]
+    fn forced() {
+        // besides the cell the subset deduction finds, nothing follows from the 1-1
+        let board = parse_board(&["*..", "oo."]);
+        assert_eq!(
+            forced_cells(&board, 1),
+            vec![
+                (Vector2::new(2, 0), Action::Open),
+                (Vector2::new(2, 1), Action::Open),
+            ]
+        );
+
+        // a 1 next to a single closed cell forces it, the mine count the far cells
+        let board = parse_board(&["o*.", "oo."]);
+        assert_eq!(
+            forced_cells(&board, 1),
+            vec![
+                (Vector2::new(1, 0), Action::Flag),
+                (Vector2::new(2, 0), Action::Open),
+                (Vector2::new(2, 1), Action::Open),
+            ]
+        );
+    }
+
+    #[test]
+    fn solvable() {
+        let board = parse_board(&["....", "....", "...*"]);
+        assert!(is_solvable(&board, &Vector2::new(0, 0)));
+
+        let board = parse_board(&["*.", "*.", ".."]);
+        assert!(!is_solvable(&board, &Vector2::new(1, 2)));
+    }
+}
@@ -0,0 +1,259 @@
+use std::io::{stdin, stdout, BufRead, Result, Write};
+
+use serde::{Deserialize, Serialize};
+use vctr2::vector2::Vector2;
+
+use crate::{
+    cli::check_game_settings,
+    game::{Game, GameStatus, RevealOutcome},
+    game_types::{CellState, CellValue, GameSettings},
+};
+
+// --- JSON lines protocol for bots --- //
+
+// Every line on stdin is one command, answered by one line on stdout:
+//
+// {"cmd": "new", "width": 30, "height": 16, "mines": 99, "seed": 42}   (all fields optional)
+// {"cmd": "reveal", "x": 3, "y": 4}
+// {"cmd": "flag", "x": 3, "y": 4}
+// {"cmd": "chord", "x": 3, "y": 4}
+//
+// {"outcome": "opened", "opened": [[3, 4]], "status": "playing", "seed": 42, "mines": 99,
+//  "board": ["##1..", ...]}
+// {"error": "unknown variant `jump`, ..."}
+//
+// Board rows use '#' for closed cells, 'F' for flags, '?' for question marks, '0' to '8' for
+// opened cells and '*' for mines, which are only shown once the game is over. Flags stay when the
+// game is lost.
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) enum Command {
+    New {
+        width: Option<u16>,
+        height: Option<u16>,
+        mines: Option<i32>,
+        seed: Option<u64>,
+    },
+    Reveal {
+        x: u16,
+        y: u16,
+    },
+    Flag {
+        x: u16,
+        y: u16,
+    },
+    Chord {
+        x: u16,
+        y: u16,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Outcome {
+    New,
+    Opened,
+    Exploded,
+    Flagged,
+    Questioned,
+    Closed,
+    Ignored,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    outcome: Outcome,
+    opened: Vec<[u16; 2]>,
+    status: &'static str,
+    seed: u64,
+    mines: i32,
+    board: Vec<String>,
+}
+
+pub(crate) struct Session {
+    game: Game,
+    /// Seed of the next game, random when `None`.
+    fixed_seed: Option<u64>,
+    no_guess: bool,
+}
+
+impl Session {
+    pub fn new(game_settings: GameSettings, fixed_seed: Option<u64>, no_guess: bool) -> Self {
+        Self {
+            game: Game::new(game_settings, fixed_seed.unwrap_or_else(rand::random))
+                .with_no_guess(no_guess),
+            fixed_seed,
+            no_guess,
+        }
+    }
+
+    /// Answers commands from stdin until it is closed.
+    pub fn run(&mut self) -> Result<()> {
+        let mut stdout = stdout().lock();
+
+        for line in stdin().lock().lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            writeln!(stdout, "{}", self.handle(&line))?;
+            stdout.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Runs one command line and returns the answer line.
+    pub fn handle(&mut self, line: &str) -> String {
+        let response = serde_json::from_str(line)
+            .map_err(|error| error.to_string())
+            .and_then(|command| self.execute(command));
+
+        match response {
+            Ok(response) => serde_json::to_string(&response),
+            Err(error) => serde_json::to_string(&serde_json::json!({ "error": error })),
+        }
+        .expect("responses are always serializable")
+    }
+
+    fn execute(&mut self, command: Command) -> std::result::Result<Response, String> {
+        let mut opened = Vec::new();
+
+        let outcome = match command {
+            Command::New {
+                width,
+                height,
+                mines,
+                seed,
+            } => {
+                let current = self.game.settings();
+                let game_settings = GameSettings {
+                    size: Vector2::new(
+                        width.unwrap_or(current.size.x),
+                        height.unwrap_or(current.size.y),
+                    ),
+                    mines: mines.unwrap_or(current.mines),
+                };
+                check_game_settings(&game_settings)?;
+
+                let seed = seed.or(self.fixed_seed).unwrap_or_else(rand::random);
+                self.game = Game::new(game_settings, seed).with_no_guess(self.no_guess);
+                Outcome::New
+            }
+            Command::Reveal { x, y } => {
+                self.check_position(x, y)?;
+                Self::reveal_outcome(self.game.reveal(&Vector2::new(x, y)), &mut opened)
+            }
+            Command::Chord { x, y } => {
+                self.check_position(x, y)?;
+                Self::reveal_outcome(self.game.chord(&Vector2::new(x, y)), &mut opened)
+            }
+            Command::Flag { x, y } => {
+                self.check_position(x, y)?;
+                match self.game.flag(&Vector2::new(x, y)) {
+                    Some(CellState::Flagged) => Outcome::Flagged,
+                    Some(CellState::Questioned) => Outcome::Questioned,
+                    Some(CellState::Closed) => Outcome::Closed,
+                    Some(CellState::Opened) | None => Outcome::Ignored,
+                }
+            }
+        };
+
+        Ok(Response {
+            outcome,
+            opened,
+            status: match self.game.status() {
+                GameStatus::Playing => "playing",
+                GameStatus::Won => "won",
+                GameStatus::Lost => "lost",
+            },
+            seed: self.game.seed(),
+            mines: self.game.settings().mines,
+            board: self.board_rows(),
+        })
+    }
+
+    fn check_position(&self, x: u16, y: u16) -> std::result::Result<(), String> {
+        let size = self.game.settings().size;
+        if x < size.x && y < size.y {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}x{} is outside of the {}x{} board",
+                x, y, size.x, size.y
+            ))
+        }
+    }
+
+    fn reveal_outcome(outcome: RevealOutcome, opened: &mut Vec<[u16; 2]>) -> Outcome {
+        match outcome {
+            RevealOutcome::Opened(cells) => {
+                opened.extend(cells.iter().map(|cell| [cell.x, cell.y]));
+                Outcome::Opened
+            }
+            RevealOutcome::Exploded(position) => {
+                opened.push([position.x, position.y]);
+                Outcome::Exploded
+            }
+            RevealOutcome::Ignored => Outcome::Ignored,
+        }
+    }
+
+    fn board_rows(&self) -> Vec<String> {
+        self.game
+            .visible_board()
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match (cell.state, cell.value) {
+                        (CellState::Closed, _) => '#',
+                        (CellState::Flagged, _) => 'F',
+                        (CellState::Questioned, _) => '?',
+                        (CellState::Opened, CellValue::Bomb) => '*',
+                        (CellState::Opened, CellValue::Empty) => {
+                            char::from_digit(cell.bombs_around as u32, 10).unwrap_or('#')
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DIFFICULY_BEGINNER;
+
+    #[test]
+    fn commands() {
+        let mut session = Session::new(DIFFICULY_BEGINNER, Some(1), false);
+
+        let answer = session.handle(r#"{"cmd": "new", "width": 4, "height": 4, "mines": 1}"#);
+        assert_eq!(
+            answer,
+            r#####"{"outcome":"new","opened":[],"status":"playing","seed":1,"mines":1,"board":["####","####","####","####"]}"#####
+        );
+
+        let answer = session.handle(r#"{"cmd": "flag", "x": 3, "y": 3}"#);
+        assert!(answer.starts_with(r#"{"outcome":"flagged""#));
+        assert!(answer.ends_with(r#####""board":["####","####","####","###F"]}"#####));
+
+        let answer = session.handle(r#"{"cmd": "reveal", "x": 0, "y": 0}"#);
+        assert!(answer.starts_with(r#"{"outcome":"opened","opened":[[0,0],"#));
+
+        assert_eq!(
+            session.handle(r#"{"cmd": "reveal", "x": 4, "y": 0}"#),
+            r#"{"error":"4x0 is outside of the 4x4 board"}"#
+        );
+        assert!(session
+            .handle(r#"{"cmd": "jump"}"#)
+            .starts_with(r#"{"error":"#));
+    }
+}
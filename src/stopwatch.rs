@@ -0,0 +1,39 @@
+use std::time::{Duration, Instant};
+
+/// Game timer that can be paused, only the time it was running counts.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        Self {
+            elapsed,
+            running_since: None,
+        }
+    }
+
+    /// Starts the stopwatch, does nothing when it is already running.
+    pub fn start(&mut self) {
+        self.running_since.get_or_insert_with(Instant::now);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(running_since) = self.running_since.take() {
+            self.elapsed += running_since.elapsed();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .running_since
+                .map_or(Duration::ZERO, |running_since| running_since.elapsed())
+    }
+}
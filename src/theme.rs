@@ -0,0 +1,294 @@
+use clap::ValueEnum;
+use ratatui::style::{Color, Modifier};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Classic,
+    Dark,
+    HighContrast,
+    Monochrome,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    /// No colours at all, every state has its own glyph
+    Symbols,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 8] = [
+        ThemeName::Classic,
+        ThemeName::Dark,
+        ThemeName::HighContrast,
+        ThemeName::Monochrome,
+        ThemeName::Protanopia,
+        ThemeName::Deuteranopia,
+        ThemeName::Tritanopia,
+        ThemeName::Symbols,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|name| *name == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ThemeName::Classic => "Classic",
+            ThemeName::Dark => "Dark",
+            ThemeName::HighContrast => "High contrast",
+            ThemeName::Monochrome => "Monochrome",
+            ThemeName::Protanopia => "Protanopia",
+            ThemeName::Deuteranopia => "Deuteranopia",
+            ThemeName::Tritanopia => "Tritanopia",
+            ThemeName::Symbols => "Symbols only",
+        }
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            ThemeName::Classic => THEME_CLASSIC,
+            ThemeName::Dark => THEME_DARK,
+            ThemeName::HighContrast => THEME_HIGH_CONTRAST,
+            ThemeName::Monochrome => THEME_MONOCHROME,
+            ThemeName::Protanopia => THEME_PROTANOPIA,
+            ThemeName::Deuteranopia => THEME_DEUTERANOPIA,
+            ThemeName::Tritanopia => THEME_TRITANOPIA,
+            ThemeName::Symbols => THEME_SYMBOLS,
+        }
+    }
+
+    /// Theme used when neither the command line nor the config picks one.
+    ///
+    /// Follows https://no-color.org: a non-empty `NO_COLOR` turns colours off.
+    pub fn from_env() -> Self {
+        match std::env::var_os("NO_COLOR") {
+            Some(value) if !value.is_empty() => ThemeName::Symbols,
+            _ => ThemeName::Classic,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyphs {
+    pub closed: char,
+    /// Opened cell without mines around
+    pub empty: char,
+    pub flag: char,
+    pub question: char,
+    pub mine: char,
+    /// The mine that ended the game
+    pub exploded: char,
+    /// Flag on a cell without a mine, shown once the game is over
+    pub wrong_flag: char,
+}
+
+pub(crate) const GLYPHS_DEFAULT: Glyphs = Glyphs {
+    closed: '#',
+    empty: ' ',
+    flag: '⚑',
+    question: '?',
+    mine: '∅',
+    exploded: '✹',
+    wrong_flag: '✗',
+};
+
+// Plain ASCII, so that no state relies on colour or on the font having the symbol
+
+pub(crate) const GLYPHS_SYMBOLS: Glyphs = Glyphs {
+    closed: '#',
+    empty: '.',
+    flag: 'F',
+    question: '?',
+    mine: '*',
+    exploded: '@',
+    wrong_flag: 'x',
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Colours of the numbers 1 to 8.
+    pub numbers: [Color; 8],
+    pub number_modifier: Modifier,
+    pub opened_bg: Color,
+    pub closed: Color,
+    pub closed_bg: Color,
+    pub flag: Color,
+    pub mine: Color,
+    pub border: Color,
+    pub cursor: Color,
+    pub title: Color,
+    pub glyphs: Glyphs,
+}
+
+impl Theme {
+    pub fn number(&self, count: u8) -> Color {
+        self.numbers[(count.clamp(1, 8) - 1) as usize]
+    }
+}
+
+// The palette of the original Windows minesweeper
+
+pub(crate) const THEME_CLASSIC: Theme = Theme {
+    numbers: [
+        Color::Rgb(0, 0, 255),
+        Color::Rgb(0, 128, 0),
+        Color::Rgb(255, 0, 0),
+        Color::Rgb(0, 0, 128),
+        Color::Rgb(128, 0, 0),
+        Color::Rgb(0, 128, 128),
+        Color::Rgb(0, 0, 0),
+        Color::Rgb(128, 128, 128),
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Rgb(192, 192, 192),
+    closed: Color::Rgb(128, 128, 128),
+    closed_bg: Color::Reset,
+    flag: Color::Red,
+    mine: Color::Red,
+    border: Color::Gray,
+    cursor: Color::Green,
+    title: Color::Rgb(228, 55, 22),
+    glyphs: GLYPHS_DEFAULT,
+};
+
+pub(crate) const THEME_DARK: Theme = Theme {
+    numbers: [
+        Color::LightBlue,
+        Color::LightGreen,
+        Color::LightRed,
+        Color::LightMagenta,
+        Color::Yellow,
+        Color::LightCyan,
+        Color::White,
+        Color::Gray,
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Rgb(38, 38, 38),
+    closed: Color::DarkGray,
+    closed_bg: Color::Reset,
+    flag: Color::LightRed,
+    mine: Color::LightRed,
+    border: Color::DarkGray,
+    cursor: Color::LightGreen,
+    title: Color::LightRed,
+    glyphs: GLYPHS_DEFAULT,
+};
+
+pub(crate) const THEME_HIGH_CONTRAST: Theme = Theme {
+    numbers: [
+        Color::Rgb(0, 170, 255),
+        Color::Rgb(0, 255, 0),
+        Color::Rgb(255, 60, 60),
+        Color::Rgb(255, 0, 255),
+        Color::Rgb(255, 255, 0),
+        Color::Rgb(0, 255, 255),
+        Color::Rgb(255, 255, 255),
+        Color::Rgb(255, 160, 0),
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Black,
+    closed: Color::White,
+    closed_bg: Color::Reset,
+    flag: Color::Rgb(255, 255, 0),
+    mine: Color::Rgb(255, 60, 60),
+    border: Color::White,
+    cursor: Color::Rgb(0, 255, 0),
+    title: Color::Rgb(255, 60, 60),
+    glyphs: GLYPHS_DEFAULT,
+};
+
+// Uses the default colours of the terminal, numbers are told apart from closed cells by glyph only
+
+pub(crate) const THEME_MONOCHROME: Theme = Theme {
+    numbers: [Color::Reset; 8],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Reset,
+    closed: Color::Reset,
+    closed_bg: Color::Reset,
+    flag: Color::Reset,
+    mine: Color::Reset,
+    border: Color::Reset,
+    cursor: Color::Reset,
+    title: Color::Reset,
+    glyphs: GLYPHS_DEFAULT,
+};
+
+// Colour-blind safe palettes, based on the Okabe–Ito colours. Neighbouring numbers also differ in
+// lightness, so they stay apart even where two hues collapse into one.
+
+pub(crate) const THEME_PROTANOPIA: Theme = Theme {
+    numbers: [
+        Color::Rgb(0, 114, 178),
+        Color::Rgb(230, 159, 0),
+        Color::Rgb(0, 0, 0),
+        Color::Rgb(86, 180, 233),
+        Color::Rgb(120, 70, 0),
+        Color::Rgb(204, 121, 167),
+        Color::Rgb(0, 60, 100),
+        Color::Rgb(96, 96, 96),
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Rgb(225, 225, 225),
+    closed: Color::Rgb(128, 128, 128),
+    closed_bg: Color::Reset,
+    flag: Color::Rgb(240, 228, 66),
+    mine: Color::Rgb(86, 180, 233),
+    border: Color::Gray,
+    cursor: Color::Rgb(240, 228, 66),
+    title: Color::Rgb(230, 159, 0),
+    glyphs: GLYPHS_DEFAULT,
+};
+
+pub(crate) const THEME_DEUTERANOPIA: Theme = Theme {
+    numbers: [
+        Color::Rgb(0, 114, 178),
+        Color::Rgb(213, 94, 0),
+        Color::Rgb(0, 0, 0),
+        Color::Rgb(86, 180, 233),
+        Color::Rgb(150, 90, 0),
+        Color::Rgb(204, 121, 167),
+        Color::Rgb(0, 60, 100),
+        Color::Rgb(96, 96, 96),
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Rgb(225, 225, 225),
+    closed: Color::Rgb(128, 128, 128),
+    closed_bg: Color::Reset,
+    flag: Color::Rgb(240, 228, 66),
+    mine: Color::Rgb(86, 180, 233),
+    border: Color::Gray,
+    cursor: Color::Rgb(240, 228, 66),
+    title: Color::Rgb(213, 94, 0),
+    glyphs: GLYPHS_DEFAULT,
+};
+
+pub(crate) const THEME_TRITANOPIA: Theme = Theme {
+    numbers: [
+        Color::Rgb(0, 120, 140),
+        Color::Rgb(213, 0, 50),
+        Color::Rgb(0, 0, 0),
+        Color::Rgb(120, 0, 60),
+        Color::Rgb(0, 70, 80),
+        Color::Rgb(255, 110, 140),
+        Color::Rgb(90, 90, 90),
+        Color::Rgb(150, 150, 150),
+    ],
+    number_modifier: Modifier::BOLD,
+    opened_bg: Color::Rgb(235, 235, 235),
+    closed: Color::Rgb(128, 128, 128),
+    closed_bg: Color::Reset,
+    flag: Color::Rgb(213, 0, 50),
+    mine: Color::Rgb(0, 120, 140),
+    border: Color::Gray,
+    cursor: Color::Rgb(213, 0, 50),
+    title: Color::Rgb(213, 0, 50),
+    glyphs: GLYPHS_DEFAULT,
+};
+
+pub(crate) const THEME_SYMBOLS: Theme = Theme {
+    glyphs: GLYPHS_SYMBOLS,
+    ..THEME_MONOCHROME
+};
@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    constants::DIFFICULY_BEGINNER,
+    demo::explain,
+    game::{Game, GameStatus},
+    game_types::{CellState, GameSettings},
+    solver::{self, Action, Deduction, Technique},
+};
+
+/// Puzzles are positions of beginner games, small enough to see at a glance.
+pub(crate) const PUZZLE_SETTINGS: GameSettings = DIFFICULY_BEGINNER;
+
+/// Games played by the solver before giving up on finding a puzzle for the technique.
+const PUZZLE_ATTEMPTS: u32 = 1000;
+
+/// A position where exactly one cell can be deduced, by any technique.
+pub(crate) struct Puzzle {
+    pub board: Board,
+    /// The board once the deduction is made, shown after the puzzle is solved.
+    pub solution: Board,
+    pub deduction: Deduction,
+}
+
+impl Puzzle {
+    /// Mines that are not flagged yet, the global mine count technique needs it.
+    pub fn mines_left(&self) -> i32 {
+        PUZZLE_SETTINGS.mines
+            - self
+                .board
+                .cells()
+                .filter(|cell| cell.state == CellState::Flagged)
+                .count() as i32
+    }
+}
+
+/// Lets the solver play seeded games one deduction at a time, until it reaches a position where
+/// a single deduction of the technique is possible.
+pub(crate) fn generate(technique: Option<Technique>, rng: &mut StdRng) -> Option<Puzzle> {
+    let size = PUZZLE_SETTINGS.size;
+
+    for _ in 0..PUZZLE_ATTEMPTS {
+        let mut game = Game::new(PUZZLE_SETTINGS, rng.gen());
+        game.reveal(&Vector2::new(size.x / 2, size.y / 2));
+
+        while game.status() == GameStatus::Playing {
+            let board = game.visible_board();
+            let deductions = solver::find_deductions_with_mine_count(&board, PUZZLE_SETTINGS.mines);
+
+            // stuck, the solver would have to guess
+            let Some(deduction) = deductions.first().copied() else {
+                break;
+            };
+
+            match deduction.action {
+                Action::Open => {
+                    game.reveal(&deduction.position);
+                }
+                Action::Flag => {
+                    game.flag(&deduction.position);
+                }
+            }
+
+            // the solver stops at the simplest technique that finds anything, only trying every
+            // placement of the mines makes sure no other cell could be the answer
+            if technique.is_none_or(|technique| deduction.technique == technique)
+                && solver::forced_cells(&board, PUZZLE_SETTINGS.mines)
+                    == [(deduction.position, deduction.action)]
+            {
+                return Some(Puzzle {
+                    board,
+                    solution: game.visible_board(),
+                    deduction,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// A session of puzzles, scored by how many are solved at the first try and how fast.
+pub(crate) struct Trainer {
+    /// Only puzzles of this technique, any when `None`.
+    pub technique: Option<Technique>,
+    pub puzzle: Option<Puzzle>,
+    /// Whether the current puzzle is solved and the next one can be started.
+    pub solved: bool,
+    started_at: Instant,
+    mistakes: u32,
+    puzzles: u32,
+    first_tries: u32,
+    total_time: Duration,
+    rng: StdRng,
+}
+
+impl Trainer {
+    pub fn new(seed: u64) -> Self {
+        let mut trainer = Self {
+            technique: None,
+            puzzle: None,
+            solved: false,
+            started_at: Instant::now(),
+            mistakes: 0,
+            puzzles: 0,
+            first_tries: 0,
+            total_time: Duration::ZERO,
+            rng: StdRng::seed_from_u64(seed),
+        };
+
+        trainer.next_puzzle();
+        trainer
+    }
+
+    pub fn next_puzzle(&mut self) {
+        self.puzzle = generate(self.technique, &mut self.rng);
+        self.solved = false;
+        self.mistakes = 0;
+        self.started_at = Instant::now();
+    }
+
+    /// Switches to the next technique filter and starts a puzzle for it.
+    pub fn next_technique(&mut self) {
+        self.technique = match self.technique {
+            None => Some(Technique::Basic),
+            Some(Technique::Basic) => Some(Technique::Subset),
+            Some(Technique::Subset) => Some(Technique::Global),
+            Some(Technique::Global) => None,
+        };
+        self.next_puzzle();
+    }
+
+    /// Checks the answer, returning the explanation when it is right and a hint when it's not.
+    pub fn answer(&mut self, position: &Vector2<u16>, action: Action) -> Result<String, String> {
+        let Some(puzzle) = &self.puzzle else {
+            return Err("No puzzle found for this technique.".to_string());
+        };
+        if self.solved {
+            return Err("Solved already, press Enter for the next puzzle.".to_string());
+        }
+
+        if puzzle.deduction.position != *position || puzzle.deduction.action != action {
+            self.mistakes += 1;
+            return Err(match action {
+                Action::Open => "That cell isn't certainly safe, try again.".to_string(),
+                Action::Flag => "That cell isn't certainly a mine, try again.".to_string(),
+            });
+        }
+
+        let time = self.started_at.elapsed();
+        self.solved = true;
+        self.puzzles += 1;
+        self.total_time += time;
+        if self.mistakes == 0 {
+            self.first_tries += 1;
+        }
+
+        Ok(format!(
+            "Right in {:.1} s! {}",
+            time.as_secs_f64(),
+            explain(&puzzle.board, &puzzle.deduction)
+        ))
+    }
+
+    /// Accuracy and speed over the session, e.g. "4 solved, 75% at the first try, 6.2 s each".
+    pub fn score(&self) -> String {
+        if self.puzzles == 0 {
+            return "No puzzles solved yet".to_string();
+        }
+
+        format!(
+            "{} solved, {}% at the first try, {:.1} s each",
+            self.puzzles,
+            self.first_tries * 100 / self.puzzles,
+            self.total_time.as_secs_f64() / self.puzzles as f64
+        )
+    }
+}
+
+pub(crate) fn technique_name(technique: Option<Technique>) -> &'static str {
+    match technique {
+        None => "any",
+        Some(Technique::Basic) => "basic",
+        Some(Technique::Subset) => "subset",
+        Some(Technique::Global) => "global mine count",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzles_have_one_answer() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for technique in [Technique::Basic, Technique::Subset, Technique::Global] {
+            for _ in 0..5 {
+                let puzzle = generate(Some(technique), &mut rng).unwrap();
+
+                assert_eq!(puzzle.deduction.technique, technique);
+                assert_eq!(
+                    solver::forced_cells(&puzzle.board, PUZZLE_SETTINGS.mines),
+                    vec![(puzzle.deduction.position, puzzle.deduction.action)]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scores_first_tries() {
+        let mut trainer = Trainer::new(2);
+        let deduction = trainer.puzzle.as_ref().unwrap().deduction;
+        let wrong = match deduction.action {
+            Action::Open => Action::Flag,
+            Action::Flag => Action::Open,
+        };
+
+        assert!(trainer.answer(&deduction.position, wrong).is_err());
+        assert!(trainer
+            .answer(&deduction.position, deduction.action)
+            .is_ok());
+        assert!(trainer.score().starts_with("1 solved, 0% at the first try"));
+    }
+}
@@ -0,0 +1,182 @@
+use vctr2::vector2::Vector2;
+
+use crate::{
+    board::Board,
+    game_types::{CellState, CellValue},
+    game_utils::open_cell,
+    solver::Action,
+};
+
+// --- Handcrafted lessons --- //
+
+// Boards are written row by row:
+//
+// o – opened          O – opened, highlighted as the numbers the lesson is about
+// . – closed, safe    x – closed mine    F – flagged mine
+// S – safe cell the player has to open  M – mine the player has to flag
+
+pub(crate) struct Lesson {
+    pub title: &'static str,
+    pub text: &'static str,
+    rows: &'static [&'static str],
+}
+
+pub(crate) const LESSONS: [Lesson; 4] = [
+    Lesson {
+        title: "Numbers",
+        text: "Every number counts the mines in the eight cells around it. \
+               The highlighted 1 has a single closed neighbour, so that cell has to be the mine. \
+               Flag it with F.",
+        rows: &["Mooo", "Oooo", "oooo"],
+    },
+    Lesson {
+        title: "Flags",
+        text: "A number that already touches as many flags as it shows has no mines left. \
+               The highlighted 1 touches a flag, so its other closed neighbours are safe. \
+               Open both with Enter.",
+        rows: &["FSSx", "oOoo", "oooo"],
+    },
+    Lesson {
+        title: "The 1-1 pattern",
+        text: "The 1 at the wall has its mine in one of two cells. The 1 next to it touches \
+               both of them, so its mine is already there and its third cell is safe. \
+               Open it.",
+        rows: &["x.Sx.", "OOooo", "ooooo"],
+    },
+    Lesson {
+        title: "The 1-2-1 pattern",
+        text: "A 2 between two 1s, with closed cells along them: the mines are always above \
+               the 1s and the cell above the 2 is safe. Flag both mines, then open every \
+               safe cell.",
+        rows: &["SMSMS", "oOOOo", "ooooo"],
+    },
+];
+
+/// A lesson being played, moves only count when they are the one the lesson asks for.
+pub(crate) struct Tutorial {
+    pub lesson: usize,
+    pub board: Board,
+    /// The numbers the lesson is about.
+    pub highlighted: Vec<Vector2<u16>>,
+    /// Moves the player still has to make.
+    targets: Vec<(Vector2<u16>, Action)>,
+}
+
+impl Tutorial {
+    pub fn new(lesson: usize) -> Self {
+        let rows = LESSONS[lesson].rows;
+        let width = rows.iter().map(|row| row.len()).min().unwrap_or(0);
+        let mut tutorial = Self {
+            lesson,
+            board: Board::new(Vector2::new(width as u16, rows.len() as u16)),
+            highlighted: Vec::new(),
+            targets: Vec::new(),
+        };
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, symbol) in row.chars().take(width).enumerate() {
+                let position = Vector2::new(x as u16, y as u16);
+                let cell = &mut tutorial.board[position];
+
+                match symbol {
+                    'o' | 'O' => cell.state = CellState::Opened,
+                    'x' | 'F' | 'M' => cell.value = CellValue::Bomb,
+                    _ => {}
+                }
+
+                match symbol {
+                    'O' => tutorial.highlighted.push(position),
+                    'F' => cell.state = CellState::Flagged,
+                    'S' => tutorial.targets.push((position, Action::Open)),
+                    'M' => tutorial.targets.push((position, Action::Flag)),
+                    _ => {}
+                }
+            }
+        }
+
+        tutorial.board.update_bombs_around();
+        tutorial
+    }
+
+    pub fn current(&self) -> &'static Lesson {
+        &LESSONS[self.lesson]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Makes the move when the lesson asks for it, otherwise tells what is wrong with it.
+    pub fn try_move(&mut self, position: &Vector2<u16>, action: Action) -> Result<(), String> {
+        let Some(index) = self.targets.iter().position(|target| target.0 == *position) else {
+            return Err(if self.board[*position].state == CellState::Opened {
+                "That cell is open already.".to_string()
+            } else {
+                "That's not the cell this lesson is about, look at the highlighted numbers again."
+                    .to_string()
+            });
+        };
+
+        if self.targets[index].1 != action {
+            return Err(match action {
+                Action::Open => "Careful, that cell is a mine!".to_string(),
+                Action::Flag => "That cell is safe, it shouldn't be flagged.".to_string(),
+            });
+        }
+
+        match action {
+            Action::Open => {
+                open_cell(&mut self.board, position);
+            }
+            Action::Flag => self.board[*position].state = CellState::Flagged,
+        }
+        self.targets.remove(index);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::find_deductions;
+
+    #[test]
+    fn lessons_are_solvable() {
+        for (index, lesson) in LESSONS.iter().enumerate() {
+            let mut tutorial = Tutorial::new(index);
+            assert!(!tutorial.is_done(), "{}", lesson.title);
+
+            // some target always follows from the visible board, until all are made
+            while !tutorial.is_done() {
+                let deductions = find_deductions(&tutorial.board);
+                let target = tutorial
+                    .targets
+                    .iter()
+                    .copied()
+                    .find(|&(position, action)| {
+                        deductions
+                            .iter()
+                            .any(|d| d.position == position && d.action == action)
+                    })
+                    .unwrap_or_else(|| panic!("{} can't be solved", lesson.title));
+
+                tutorial.try_move(&target.0, target.1).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_moves() {
+        let mut tutorial = Tutorial::new(0);
+
+        assert!(tutorial
+            .try_move(&Vector2::new(0, 0), Action::Open)
+            .is_err());
+        assert!(tutorial
+            .try_move(&Vector2::new(1, 1), Action::Flag)
+            .is_err());
+        assert!(tutorial.try_move(&Vector2::new(0, 0), Action::Flag).is_ok());
+        assert!(tutorial.is_done());
+    }
+}